@@ -58,6 +58,7 @@
             meta.mainProgram = "web";
             srcs = [
               ./lib
+              ./test-support
               ./web
             ];
             sourceRoot = "./web";
@@ -1,5 +1,6 @@
 pub mod provider;
 pub mod source;
+pub mod statistics;
 pub mod types;
 
 #[cfg(feature = "svg")]
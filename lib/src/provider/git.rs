@@ -1,6 +1,6 @@
 use super::Result;
-use crate::types::{ContributionActivity, Error, YEAR};
-use chrono::{DateTime, NaiveDate};
+use crate::types::{ContributionActivity, DateRange, Error};
+use chrono::DateTime;
 use git2::{build::RepoBuilder, FetchOptions, RemoteCallbacks, Sort};
 use std::{
     collections::BTreeMap,
@@ -67,14 +67,15 @@ impl Repository {
     /// Get activity of the specified `user` in the last year.
     /// `user` matches both an author's name or email.
     pub async fn get_activity(&self, user: String) -> Result<ContributionActivity> {
-        let one_year_ago = chrono::Local::now().date_naive() - YEAR;
-        self.get_activity_since(user, one_year_ago).await
+        self.get_activity_in(user, &DateRange::last_year()).await
     }
 
-    async fn get_activity_since(
+    /// Get activity of the specified `user` within `range`.
+    /// `user` matches both an author's name or email.
+    pub async fn get_activity_in(
         &self,
         user: String,
-        since: NaiveDate,
+        range: &DateRange,
     ) -> Result<ContributionActivity> {
         let repository = self.0.lock().unwrap();
         let mut revwalk = repository.revwalk()?;
@@ -93,7 +94,7 @@ impl Repository {
                 ))?
                 .date_naive();
 
-            if commit_time >= since
+            if range.contains(&commit_time)
                 && (commit.author().name() == Some(&user) || commit.author().email() == Some(&user))
             {
                 *result.entry(commit_time).or_insert(0) += 1;
@@ -106,33 +107,34 @@ impl Repository {
 
 #[cfg(test)]
 mod tests {
-    use crate::provider::git::Repository;
+    use crate::{provider::git::Repository, types::DateRange};
     use chrono::NaiveDate;
+    use commitoria_test_support::GitRepository;
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn git_repository() {
-        let repository = Repository::new(
-            "https://github.com/thomas-zahner/commitoria"
-                .try_into()
-                .unwrap(),
-        )
-        .await;
-        let since = NaiveDate::from_ymd_opt(2024, 01, 01).unwrap();
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let origin = GitRepository::new();
+        origin
+            .commit("Thomas Zahner", "thomas@example.com", date(1, 1))
+            .commit("Thomas Zahner", "thomas@example.com", date(12, 11))
+            .commit("Someone Else", "someone@example.com", date(12, 12))
+            .commit("Thomas Zahner", "thomas@example.com", date(12, 13))
+            .commit("Thomas Zahner", "thomas@example.com", date(12, 13))
+            .commit("Anonymous", "thomas@example.com", date(12, 13));
+
+        let repository = Repository::new(origin.url()).await.unwrap();
+        let range = DateRange::new(date(1, 2), date(12, 31)).unwrap();
 
         let result = repository
-            .unwrap()
-            .get_activity_since("Thomas Zahner".into(), since)
+            .get_activity_in("Thomas Zahner".into(), &range)
             .await
             .unwrap();
 
-        assert!(result.contribution_count() > 100);
-        assert_eq!(
-            result.get(&NaiveDate::from_ymd_opt(2024, 12, 12).unwrap()),
-            None
-        );
-        assert_eq!(
-            result.get(&NaiveDate::from_ymd_opt(2024, 12, 13).unwrap()),
-            Some(3)
-        );
+        assert_eq!(result.contribution_count(), 3);
+        assert_eq!(result.get(&date(1, 1)), None);
+        assert_eq!(result.get(&date(12, 11)), Some(1));
+        assert_eq!(result.get(&date(12, 12)), None);
+        assert_eq!(result.get(&date(12, 13)), Some(2));
     }
 }
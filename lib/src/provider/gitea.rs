@@ -1,6 +1,6 @@
 use crate::{
     source::DataSource,
-    types::{ContributionActivity, Error},
+    types::{ContributionActivity, DateRange, Error},
 };
 use chrono::{DateTime, NaiveDate};
 use reqwest::Url;
@@ -67,18 +67,32 @@ impl Gitea {
         let parsed: Vec<HeatmapDataPoint> = serde_json::from_str(&json)?;
         parsed.try_into()
     }
+
+    /// Fetch the activity within `range`.
+    /// Only the last year is available, so earlier days of `range` remain empty.
+    pub async fn fetch_range<S: DataSource>(
+        data_source: S,
+        user_name: String,
+        url: Url,
+        range: &DateRange,
+    ) -> Result<ContributionActivity> {
+        Ok(Self::fetch(data_source, user_name, url)
+            .await?
+            .within(range))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::source::{FixtureDataSource, ReqwestDataSource};
+    use crate::source::{ReplayDataSource, ReqwestDataSource};
+    use commitoria_test_support::{Contributions, MockForge};
 
     #[tokio::test]
     async fn contributions_fixture() {
         let result = Gitea::fetch(
-            FixtureDataSource::GiteaUser,
-            "".into(),
+            ReplayDataSource::fixtures(),
+            "unfa".into(),
             "https://codeberg.org".try_into().unwrap(),
         )
         .await
@@ -98,31 +112,26 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn contributions_real_codeberg() {
-        let result = Gitea::fetch(
-            ReqwestDataSource {},
-            "unfa".into(),
-            "https://codeberg.org".try_into().unwrap(),
-        )
-        .await;
-        assert!(result.is_ok());
-    }
-
-    #[tokio::test]
-    async fn contributions_real_forgejo() {
-        let result = Gitea::fetch(
-            ReqwestDataSource {},
-            "kirylkaveryn".into(),
-            "https://git.omaps.dev".try_into().unwrap(),
-        )
-        .await;
-        assert!(result.is_ok());
+    async fn contributions_mock_forge() {
+        let day = NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let forge = MockForge::with_user("unfa", Contributions::from([(day, 3)])).await;
+
+        let result = Gitea::fetch(ReqwestDataSource {}, "unfa".into(), forge.url())
+            .await
+            .unwrap();
+        assert_eq!(result.get(&day), Some(3));
+        assert_eq!(result.contribution_count(), 3);
+
+        for user_name in ["", "kirylkaveryn"] {
+            let result = Gitea::fetch(ReqwestDataSource {}, user_name.into(), forge.url()).await;
+            assert_eq!(result, Result::Err(Error::UserNotFound));
+        }
     }
 
     #[tokio::test]
     async fn user_not_found() {
         let result = Gitea::fetch(
-            ReqwestDataSource {},
+            ReplayDataSource::fixtures(),
             "".into(),
             "https://codeberg.org".try_into().unwrap(),
         )
@@ -1,11 +1,17 @@
 use super::{parse_date, Error};
-use crate::{source::DataSource, types::ContributionActivity, types::Result};
+use crate::{
+    source::DataSource,
+    types::{ContributionActivity, DateRange, Result},
+};
 use regex::Regex;
 use scraper::{Html, Selector};
 use std::{collections::BTreeMap, sync::LazyLock};
+use url::Url;
 
 pub struct Github {}
 
+const GITHUB_URL: &str = "https://github.com";
+
 static GITHUB_CONTRIBUTION_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new("^(\\d+) contributions?").unwrap());
 
@@ -14,12 +20,64 @@ impl Github {
         data_source: S,
         user_name: String,
     ) -> Result<ContributionActivity> {
-        let html = data_source
-            .fetch(format!(
-                "https://github.com/users/{user_name}/contributions"
-            ))
-            .await?;
-        let document = Html::parse_document(&html);
+        Self::fetch_from(data_source, user_name, Url::parse(GITHUB_URL).unwrap()).await
+    }
+
+    /// Like `fetch` but from a GitHub instance other than https://github.com, such as GitHub Enterprise
+    pub async fn fetch_from<S: DataSource>(
+        data_source: S,
+        user_name: String,
+        url: Url,
+    ) -> Result<ContributionActivity> {
+        let url = Self::contributions_url(url, &user_name)?;
+        Self::parse(&data_source.fetch(url).await?)
+    }
+
+    /// Fetch the activity within `range` instead of the last year
+    pub async fn fetch_range<S: DataSource>(
+        data_source: S,
+        user_name: String,
+        range: &DateRange,
+    ) -> Result<ContributionActivity> {
+        let url = Url::parse(GITHUB_URL).unwrap();
+        Self::fetch_range_from(data_source, user_name, url, range).await
+    }
+
+    /// Like `fetch_range` but from a GitHub instance other than https://github.com.
+    /// GitHub serves at most a year at once, so longer ranges are fetched year by year.
+    pub async fn fetch_range_from<S: DataSource>(
+        data_source: S,
+        user_name: String,
+        url: Url,
+        range: &DateRange,
+    ) -> Result<ContributionActivity> {
+        let mut url = Self::contributions_url(url, &user_name)?;
+        let mut activity = ContributionActivity::new();
+
+        for year in range.years() {
+            url.query_pairs_mut()
+                .clear()
+                .append_pair("from", &year.from().to_string())
+                .append_pair("to", &year.to().to_string());
+            activity += Self::parse(&data_source.fetch(url.clone()).await?)?.within(&year);
+        }
+
+        Ok(activity)
+    }
+
+    /// The contributions page of `user_name` below `url`, keeping any path prefix such as
+    /// the mount point of a proxy
+    fn contributions_url(mut url: Url, user_name: &str) -> Result<Url> {
+        let invalid = Error::InvalidUrl(url.to_string());
+        url.path_segments_mut()
+            .map_err(|_| invalid)?
+            .pop_if_empty()
+            .extend(["users", user_name, "contributions"]);
+        Ok(url)
+    }
+
+    fn parse(html: &str) -> Result<ContributionActivity> {
+        let document = Html::parse_document(html);
         let selector = Selector::parse("div > table > tbody td[data-date]")?;
 
         let activities = document
@@ -59,12 +117,13 @@ impl Github {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::source::{FixtureDataSource, ReqwestDataSource};
+    use crate::source::{ReplayDataSource, ReqwestDataSource};
     use chrono::NaiveDate;
+    use commitoria_test_support::{Contributions, MockForge};
 
     #[tokio::test]
     async fn contributions_fixture() {
-        let result = Github::fetch(FixtureDataSource::GithubUser, "".into())
+        let result = Github::fetch(ReplayDataSource::fixtures(), "thomas-zahner".into())
             .await
             .unwrap();
 
@@ -81,14 +140,84 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn contributions_real() {
-        let result = Github::fetch(ReqwestDataSource {}, "mre".into()).await;
-        assert!(result.is_ok());
+    async fn contributions_mock_forge() {
+        let day = NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let forge = MockForge::with_user(
+            "mre",
+            Contributions::from([(day.pred_opt().unwrap(), 0), (day, 3)]),
+        )
+        .await;
+
+        let result = Github::fetch_from(ReqwestDataSource {}, "mre".into(), forge.url())
+            .await
+            .unwrap();
+        assert_eq!(result.get(&day.pred_opt().unwrap()), Some(0));
+        assert_eq!(result.get(&day), Some(3));
+
+        let result = Github::fetch_from(ReqwestDataSource {}, "".into(), forge.url()).await;
+        assert_eq!(result, Result::Err(Error::UserNotFound));
+    }
+
+    #[tokio::test]
+    async fn contributions_range() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let forge = MockForge::with_user(
+            "mre",
+            Contributions::from([
+                (date(2021, 12, 31), 1),
+                (date(2022, 1, 1), 2),
+                (date(2023, 6, 1), 3),
+                (date(2024, 1, 1), 4),
+            ]),
+        )
+        .await;
+
+        let range = DateRange::new(date(2022, 1, 1), date(2023, 12, 31)).unwrap();
+        let result =
+            Github::fetch_range_from(ReqwestDataSource {}, "mre".into(), forge.url(), &range)
+                .await
+                .unwrap();
+
+        assert_eq!(result.get(&date(2021, 12, 31)), None);
+        assert_eq!(result.get(&date(2022, 1, 1)), Some(2));
+        assert_eq!(result.get(&date(2023, 6, 1)), Some(3));
+        assert_eq!(result.get(&date(2024, 1, 1)), None);
+        assert_eq!(result.contribution_count(), 5);
+    }
+
+    #[tokio::test]
+    async fn contributions_base_path() {
+        let day = NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let forge = MockForge::start_below(
+            "github",
+            BTreeMap::from([("mre".to_owned(), Contributions::from([(day, 3)]))]),
+        )
+        .await;
+        let url = forge.url();
+        assert_eq!(url.path(), "/github/");
+
+        let result = Github::fetch_from(ReqwestDataSource {}, "mre".into(), url.clone())
+            .await
+            .unwrap();
+        assert_eq!(result.get(&day), Some(3));
+
+        let range = DateRange::new(day, day).unwrap();
+        let result = Github::fetch_range_from(ReqwestDataSource {}, "mre".into(), url, &range)
+            .await
+            .unwrap();
+        assert_eq!(result.get(&day), Some(3));
+
+        let url = Url::parse("mailto:mre@example.com").unwrap();
+        let result = Github::fetch_from(ReqwestDataSource {}, "mre".into(), url).await;
+        assert_eq!(
+            result,
+            Err(Error::InvalidUrl("mailto:mre@example.com".into()))
+        );
     }
 
     #[tokio::test]
     async fn user_not_found() {
-        let result = Github::fetch(ReqwestDataSource {}, "".into()).await;
+        let result = Github::fetch(ReplayDataSource::fixtures(), "".into()).await;
         assert_eq!(result, Result::Err(Error::UserNotFound));
     }
 }
@@ -1,4 +1,8 @@
-use crate::{provider::parse_date, source::DataSource, types::ContributionActivity};
+use crate::{
+    provider::parse_date,
+    source::DataSource,
+    types::{ContributionActivity, DateRange},
+};
 use chrono::NaiveDate;
 use std::collections::{BTreeMap, HashMap};
 use url::Url;
@@ -25,21 +29,35 @@ impl Gitlab {
             .collect::<Result<BTreeMap<_, _>>>()?
             .into())
     }
+
+    /// Fetch the activity within `range`.
+    /// Only the last year is available, so earlier days of `range` remain empty.
+    pub async fn fetch_range<S: DataSource>(
+        data_source: S,
+        user_name: String,
+        url: Url,
+        range: &DateRange,
+    ) -> Result<ContributionActivity> {
+        Ok(Self::fetch(data_source, user_name, url)
+            .await?
+            .within(range))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        source::{FixtureDataSource, ReqwestDataSource},
+        source::{ReplayDataSource, ReqwestDataSource},
         types::Error,
     };
+    use commitoria_test_support::{Contributions, MockForge};
 
     #[tokio::test]
     async fn contributions_fixture() {
         let result = Gitlab::fetch(
-            FixtureDataSource::GitlabUser,
-            "".into(),
+            ReplayDataSource::fixtures(),
+            "thomas-zahner".into(),
             "https://gitlab.com".try_into().unwrap(),
         )
         .await
@@ -63,20 +81,24 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn contributions_real() {
-        let result = Gitlab::fetch(
-            ReqwestDataSource {},
-            "thomas-zahner".into(),
-            "https://gitlab.com".try_into().unwrap(),
-        )
-        .await;
-        assert!(result.is_ok());
+    async fn contributions_mock_forge() {
+        let day = NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let forge = MockForge::with_user("thomas-zahner", Contributions::from([(day, 3)])).await;
+
+        let result = Gitlab::fetch(ReqwestDataSource {}, "thomas-zahner".into(), forge.url())
+            .await
+            .unwrap();
+        assert_eq!(result.get(&day), Some(3));
+        assert_eq!(result.contribution_count(), 3);
+
+        let result = Gitlab::fetch(ReqwestDataSource {}, "".into(), forge.url()).await;
+        assert_eq!(result, Result::Err(Error::UserNotFound));
     }
 
     #[tokio::test]
     async fn user_not_found() {
         let result = Gitlab::fetch(
-            ReqwestDataSource {},
+            ReplayDataSource::fixtures(),
             "".into(),
             "https://gitlab.com".try_into().unwrap(),
         )
@@ -10,7 +10,7 @@ pub mod github;
 pub mod gitlab;
 
 /// Try to parse a `&str` to a `NaiveDate`
-fn parse_date(date: &str) -> Result<NaiveDate> {
+pub(crate) fn parse_date(date: &str) -> Result<NaiveDate> {
     const DATE_DESCRIPTION: &'static str = "%Y-%m-%d";
 
     NaiveDate::parse_from_str(date, DATE_DESCRIPTION)
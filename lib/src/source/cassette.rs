@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use reqwest::{IntoUrl, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use super::DataSource;
+use crate::types::{Error, Result};
+
+/// A single recorded HTTP interaction.
+/// The metadata is stored as `<key>.json` and the body next to it as `<key>.body`,
+/// so that recorded pages remain readable and diffable.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub url: String,
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub body: String,
+}
+
+impl Interaction {
+    /// Map the recorded response the same way `ReqwestDataSource` maps a live response
+    fn into_result(self) -> Result<String> {
+        match StatusCode::from_u16(self.status) {
+            Ok(StatusCode::NOT_FOUND) => Err(Error::UserNotFound),
+            Ok(status) if status.is_success() => Ok(self.body),
+            _ => Err(Error::ReqwestError(format!(
+                "HTTP status {} for url ({})",
+                self.status, self.url
+            ))),
+        }
+    }
+}
+
+/// Derive the file name of a cassette entry from its URL.
+/// The scheme is dropped and every character which is not safe in a file name is replaced by `_`.
+fn key(url: &Url) -> String {
+    url.as_str()
+        .trim_start_matches(url.scheme())
+        .trim_start_matches("://")
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+fn cassette_error(error: impl ToString) -> Error {
+    Error::CassetteError(error.to_string())
+}
+
+/// Fetches from the internet and records each interaction into a cassette directory,
+/// so that it can later be served by `ReplayDataSource`.
+pub struct RecordingDataSource {
+    directory: PathBuf,
+}
+
+impl RecordingDataSource {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn write(&self, key: &str, interaction: &Interaction) -> Result<()> {
+        fs::create_dir_all(&self.directory).map_err(cassette_error)?;
+        let metadata = serde_json::to_string_pretty(interaction)?;
+        fs::write(self.directory.join(format!("{key}.json")), metadata + "\n")
+            .map_err(cassette_error)?;
+        fs::write(
+            self.directory.join(format!("{key}.body")),
+            &interaction.body,
+        )
+        .map_err(cassette_error)
+    }
+}
+
+impl DataSource for RecordingDataSource {
+    async fn fetch<T: IntoUrl>(&self, source: T) -> Result<String> {
+        let url = source.into_url()?;
+        let response = reqwest::get(url.clone()).await?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect();
+
+        let interaction = Interaction {
+            url: url.to_string(),
+            status,
+            headers,
+            body: response.text().await?,
+        };
+
+        self.write(&key(&url), &interaction)?;
+        interaction.into_result()
+    }
+}
+
+/// Serves interactions previously captured by `RecordingDataSource` without any network access.
+pub struct ReplayDataSource {
+    directory: PathBuf,
+}
+
+impl ReplayDataSource {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// The cassettes shipped with this crate, used throughout the test-suite
+    #[cfg(test)]
+    pub(crate) fn fixtures() -> Self {
+        Self::new("fixtures/cassettes")
+    }
+
+    /// Read the recorded interaction for `url`
+    pub fn read(&self, url: &Url) -> Result<Interaction> {
+        let key = key(url);
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|e| cassette_error(format!("{}: {e}", path.display())))
+        };
+
+        let metadata = read(&self.directory.join(format!("{key}.json")))?;
+        let mut interaction: Interaction = serde_json::from_str(&metadata)?;
+
+        if interaction.url != url.as_str() {
+            return Err(cassette_error(format!(
+                "cassette {key} was recorded for {} instead of {url}",
+                interaction.url
+            )));
+        }
+
+        interaction.body = read(&self.directory.join(format!("{key}.body")))?;
+        Ok(interaction)
+    }
+}
+
+impl DataSource for ReplayDataSource {
+    async fn fetch<T: IntoUrl>(&self, source: T) -> Result<String> {
+        self.read(&source.into_url()?)?.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use commitoria_test_support::{Contributions, MockForge};
+
+    #[test]
+    fn cassette_key() {
+        let url = Url::parse("https://codeberg.org/unfa?tab=activity").unwrap();
+        assert_eq!(key(&url), "codeberg.org_unfa_tab_activity");
+
+        let url = Url::parse("https://github.com/users//contributions").unwrap();
+        assert_eq!(key(&url), "github.com_users__contributions");
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        let result = ReplayDataSource::fixtures()
+            .fetch("https://gitlab.com/users/thomas-zahner/calendar.json")
+            .await
+            .unwrap();
+        assert!(result.starts_with(r#"{"2024-01-22":1,"#));
+    }
+
+    #[tokio::test]
+    async fn replay_not_found() {
+        let result = ReplayDataSource::fixtures()
+            .fetch("https://gitlab.com/users//calendar.json")
+            .await;
+        assert_eq!(result, Err(Error::UserNotFound));
+    }
+
+    #[tokio::test]
+    async fn replay_missing_cassette() {
+        let result = ReplayDataSource::fixtures()
+            .fetch("https://example.com/not-recorded")
+            .await;
+        assert!(matches!(result, Err(Error::CassetteError(_))));
+    }
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let forge = MockForge::with_user("someone", Contributions::from([(date, 3)])).await;
+        let url = forge.url().join("users/someone/calendar.json").unwrap();
+        let directory = std::env::temp_dir().join(format!("commitoria-{}", std::process::id()));
+
+        let recorded = RecordingDataSource::new(&directory)
+            .fetch(url.clone())
+            .await;
+        drop(forge);
+        let replayed = ReplayDataSource::new(&directory).fetch(url.clone()).await;
+        let interaction = ReplayDataSource::new(&directory).read(&url).unwrap();
+        fs::remove_dir_all(directory).unwrap();
+
+        assert_eq!(recorded, Ok(r#"{"2024-12-13":3}"#.into()));
+        assert_eq!(replayed, recorded);
+        assert_eq!(interaction.status, 200);
+        assert_eq!(
+            interaction.headers.get("content-type").map(String::as_str),
+            Some("application/json; charset=utf-8")
+        );
+    }
+}
@@ -4,6 +4,10 @@ use reqwest::{IntoUrl, StatusCode};
 
 use crate::types::{Error, Result};
 
+pub mod cassette;
+
+pub use cassette::{RecordingDataSource, ReplayDataSource};
+
 pub trait DataSource {
     fn fetch<T: IntoUrl>(&self, source: T) -> impl Future<Output = Result<String>>;
 }
@@ -23,23 +27,3 @@ impl DataSource for ReqwestDataSource {
             .await?)
     }
 }
-
-#[cfg(test)]
-pub enum FixtureDataSource {
-    GithubUser,
-    GitlabUser,
-    GiteaUser,
-}
-
-#[cfg(test)]
-impl DataSource for FixtureDataSource {
-    async fn fetch<T: IntoUrl>(&self, _: T) -> Result<String> {
-        let fixture_path = match self {
-            Self::GithubUser => "fixtures/github.html",
-            Self::GitlabUser => "fixtures/gitlab.json",
-            Self::GiteaUser => "fixtures/gitea.html",
-        };
-
-        Ok(std::fs::read_to_string(fixture_path).expect("Unable to read file"))
-    }
-}
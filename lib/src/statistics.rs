@@ -0,0 +1,242 @@
+use chrono::{Datelike, Days, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::types::ContributionActivity;
+
+/// Consecutive days with at least one contribution
+#[derive(Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Streak {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: usize,
+}
+
+/// A single day and its contribution count
+#[derive(Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// Contribution counts of active days at the given percentiles, using the nearest-rank method
+#[derive(Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Percentiles {
+    pub p25: usize,
+    pub p50: usize,
+    pub p75: usize,
+    pub p90: usize,
+    pub p99: usize,
+}
+
+/// Summary of a `ContributionActivity`.
+/// A day is considered active if it has at least one contribution.
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct Statistics {
+    pub contribution_count: usize,
+    pub active_days: usize,
+    /// The streak which includes today or, as today might not be over yet, yesterday
+    pub current_streak: Option<Streak>,
+    pub longest_streak: Option<Streak>,
+    pub busiest_day: Option<Day>,
+    /// Contributions per weekday, starting with Monday
+    pub weekday_totals: [usize; 7],
+    /// Contributions per month, keyed by `YYYY-MM`
+    pub month_totals: BTreeMap<String, usize>,
+    pub average_per_active_day: f32,
+    pub percentiles: Option<Percentiles>,
+}
+
+impl Statistics {
+    pub fn new(activity: &ContributionActivity) -> Self {
+        Self::at(activity, chrono::Local::now().date_naive())
+    }
+
+    /// Compute the statistics as seen on `today`
+    pub fn at(activity: &ContributionActivity, today: NaiveDate) -> Self {
+        let active = activity
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .collect::<Vec<_>>();
+
+        let mut weekday_totals = [0; 7];
+        let mut month_totals = BTreeMap::new();
+        for (date, count) in &active {
+            weekday_totals[date.weekday().num_days_from_monday() as usize] += count;
+            *month_totals
+                .entry(date.format("%Y-%m").to_string())
+                .or_insert(0) += count;
+        }
+
+        let streaks = streaks(&active);
+        let yesterday = today - Days::new(1);
+        let current_streak = streaks
+            .last()
+            .filter(|streak| streak.to == today || streak.to == yesterday)
+            .copied();
+        let longest_streak = streaks.iter().max_by_key(|streak| streak.days).copied();
+
+        let busiest_day = active
+            .iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(date, count)| Day {
+                date: *date,
+                count: *count,
+            });
+
+        let mut counts = active.iter().map(|(_, count)| *count).collect::<Vec<_>>();
+        counts.sort_unstable();
+        let contribution_count = counts.iter().sum();
+        let average_per_active_day = match counts.len() {
+            0 => 0.0,
+            days => contribution_count as f32 / days as f32,
+        };
+
+        let percentiles = (!counts.is_empty()).then(|| Percentiles {
+            p25: percentile(&counts, 25),
+            p50: percentile(&counts, 50),
+            p75: percentile(&counts, 75),
+            p90: percentile(&counts, 90),
+            p99: percentile(&counts, 99),
+        });
+
+        Self {
+            contribution_count,
+            active_days: active.len(),
+            current_streak,
+            longest_streak,
+            busiest_day,
+            weekday_totals,
+            month_totals,
+            average_per_active_day,
+            percentiles,
+        }
+    }
+}
+
+/// The value at percentile `p` of the non-empty and ascendingly sorted `values`
+pub(crate) fn percentile(sorted: &[usize], p: usize) -> usize {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Group the chronologically ordered active days into streaks
+fn streaks(active: &[(NaiveDate, usize)]) -> Vec<Streak> {
+    let mut result: Vec<Streak> = vec![];
+
+    for (date, _) in active {
+        match result.last_mut() {
+            Some(streak) if streak.to + Days::new(1) == *date => {
+                streak.to = *date;
+                streak.days += 1;
+            }
+            _ => result.push(Streak {
+                from: *date,
+                to: *date,
+                days: 1,
+            }),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn activity() -> ContributionActivity {
+        BTreeMap::from([
+            (date(1, 1), 1),
+            (date(1, 2), 4),
+            (date(1, 3), 2),
+            (date(1, 4), 0),
+            (date(1, 5), 1),
+            (date(2, 28), 3),
+            (date(2, 29), 5),
+        ])
+        .into()
+    }
+
+    #[test]
+    fn streaks() {
+        let statistics = Statistics::at(&activity(), date(3, 1));
+        assert_eq!(
+            statistics.longest_streak,
+            Some(Streak {
+                from: date(1, 1),
+                to: date(1, 3),
+                days: 3
+            })
+        );
+        assert_eq!(
+            statistics.current_streak,
+            Some(Streak {
+                from: date(2, 28),
+                to: date(2, 29),
+                days: 2
+            })
+        );
+
+        let statistics = Statistics::at(&activity(), date(3, 2));
+        assert_eq!(statistics.current_streak, None);
+    }
+
+    #[test]
+    fn totals() {
+        let statistics = Statistics::at(&activity(), date(3, 1));
+        assert_eq!(statistics.contribution_count, 16);
+        assert_eq!(statistics.active_days, 6);
+        assert_eq!(statistics.average_per_active_day, 16.0 / 6.0);
+        assert_eq!(
+            statistics.busiest_day,
+            Some(Day {
+                date: date(2, 29),
+                count: 5
+            })
+        );
+        // 2024-01-01 was a Monday
+        assert_eq!(statistics.weekday_totals, [1, 4, 5, 5, 1, 0, 0]);
+        assert_eq!(
+            statistics.month_totals,
+            BTreeMap::from([("2024-01".into(), 8), ("2024-02".into(), 8)])
+        );
+    }
+
+    #[test]
+    fn percentiles() {
+        let statistics = Statistics::at(&activity(), date(3, 1));
+        assert_eq!(
+            statistics.percentiles,
+            Some(Percentiles {
+                p25: 1,
+                p50: 2,
+                p75: 4,
+                p90: 5,
+                p99: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let statistics = Statistics::at(&ContributionActivity::new(), date(3, 1));
+        assert_eq!(statistics.contribution_count, 0);
+        assert_eq!(statistics.average_per_active_day, 0.0);
+        assert_eq!(statistics.longest_streak, None);
+        assert_eq!(statistics.busiest_day, None);
+        assert_eq!(statistics.percentiles, None);
+    }
+
+    #[test]
+    fn serialize() {
+        let statistics = Statistics::at(&activity(), date(3, 1));
+        let json = serde_json::to_value(statistics).unwrap();
+        assert_eq!(json["busiest_day"]["date"], "2024-02-29");
+        assert_eq!(json["longest_streak"]["days"], 3);
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::{Days, NaiveDate};
+
+use crate::types::DateRange;
+
+/// Context drawn over the cells without changing their colours
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Annotations {
+    /// Outline the longest run of consecutive active days of each calendar
+    pub longest_streak: bool,
+    /// The day to mark as today
+    pub today: Option<NaiveDate>,
+    pub holidays: Vec<Holiday>,
+    /// Texts attached to single days, such as releases
+    pub labels: Vec<(NaiveDate, String)>,
+}
+
+/// A named period shaded in the calendar, such as a public holiday or leave
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    pub name: String,
+    pub range: DateRange,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidIcsError(pub String);
+
+/// Read the events of an iCalendar (`.ics`) file as holidays.
+/// Only the dates of `DTSTART` and `DTEND` are used, so times and time zones are ignored.
+/// The end date of all-day events is exclusive, as in the specification.
+pub fn parse_ics(ics: &str) -> Result<Vec<Holiday>, InvalidIcsError> {
+    let mut holidays = vec![];
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+
+    for line in unfold(ics) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as the time zone are ignored
+        let name = name.split(';').next().unwrap_or_default();
+
+        match (name.to_ascii_uppercase().as_str(), &mut event) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some((None, None, String::new()))
+            }
+            ("END", Some((start, end, summary))) if value.eq_ignore_ascii_case("VEVENT") => {
+                let from = start.ok_or_else(|| {
+                    InvalidIcsError(format!("The event {summary:?} has no DTSTART"))
+                })?;
+                let to = end.unwrap_or(from).max(from);
+                holidays.push(Holiday {
+                    name: summary.clone(),
+                    range: DateRange::new(from, to).expect("the end is not before the start"),
+                });
+                event = None;
+            }
+            ("DTSTART", Some((start, _, _))) => *start = Some(parse_ics_date(value)?),
+            ("DTEND", Some((start, end, _))) => {
+                let date = parse_ics_date(value)?;
+                let all_day = value.len() == 8;
+                *end = match all_day && Some(date) > *start {
+                    true => date.checked_sub_days(Days::new(1)),
+                    false => Some(date),
+                };
+            }
+            ("SUMMARY", Some((_, _, summary))) => *summary = unescape(value),
+            _ => {}
+        }
+    }
+
+    Ok(holidays)
+}
+
+/// The content lines of `ics`, joining lines continued by leading whitespace
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in ics.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+/// The date of a `DATE` or `DATE-TIME` value such as `20240325` or `20240325T090000Z`
+fn parse_ics_date(value: &str) -> Result<NaiveDate, InvalidIcsError> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| InvalidIcsError(format!("Invalid date {value:?}")))
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => result.push('\n'),
+                Some(escaped) => result.push(escaped),
+                None => {}
+            },
+            char => result.push(char),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ics, Holiday, InvalidIcsError};
+    use crate::types::DateRange;
+    use chrono::NaiveDate;
+
+    fn holiday(name: &str, from: (u32, u32), to: (u32, u32)) -> Holiday {
+        let date = |(month, day)| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        Holiday {
+            name: name.into(),
+            range: DateRange::new(date(from), date(to)).unwrap(),
+        }
+    }
+
+    #[test]
+    fn ics() {
+        let ics = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20241225\r
+DTEND;VALUE=DATE:20241227\r
+SUMMARY:Christmas\\, Boxing Day\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Parental leave in\r
+  spring\r
+DTSTART;TZID=Europe/Berlin:20240401T090000\r
+DTEND;TZID=Europe/Berlin:20240412T170000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20240501\r
+SUMMARY:Labour Day\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+        assert_eq!(
+            parse_ics(ics),
+            Ok(vec![
+                holiday("Christmas, Boxing Day", (12, 25), (12, 26)),
+                holiday("Parental leave in spring", (4, 1), (4, 12)),
+                holiday("Labour Day", (5, 1), (5, 1)),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_ics() {
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\nDTSTART:2024-12-25\nEND:VEVENT"),
+            Err(InvalidIcsError(r#"Invalid date "2024-12-25""#.into()))
+        );
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\nSUMMARY:Leave\nEND:VEVENT"),
+            Err(InvalidIcsError(
+                r#"The event "Leave" has no DTSTART"#.into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,573 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use super::contribution_colour::ContributionInfo;
+use super::locale::capitalise;
+use super::svg_renderer::{self, summarise, BuilderError, SvgRenderer};
+use crate::types::{ContributionActivity, DateRange};
+
+const WIDTH_DEFAULT: usize = 640;
+const HEIGHT_DEFAULT: usize = 240;
+const PADDING: f64 = 6.0;
+/// The number of gridlines above the axis aimed for
+const TICKS: usize = 4;
+
+/// The interval contributions are summed up over
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// ISO weeks, starting on Monday
+    #[default]
+    Week,
+    Month,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownPeriodError(pub String);
+
+impl FromStr for Period {
+    type Err = UnknownPeriodError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            _ => Err(UnknownPeriodError(name.into())),
+        }
+    }
+}
+
+impl Period {
+    /// The first day of the period containing `date`
+    fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            Period::Month => date.with_day(1).expect("every month has a first day"),
+        }
+    }
+
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => start + Days::new(7),
+            Period::Month => start + Months::new(1),
+        }
+    }
+
+    /// The ISO notation of the period starting at `start`, e.g. `2024-W05` or `2024-02`
+    fn key(&self, start: NaiveDate) -> String {
+        match self {
+            Period::Week => {
+                let week = start.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Period::Month => format!("{}-{:02}", start.year(), start.month()),
+        }
+    }
+
+    /// The contribution count of every period overlapping `range`, keyed by the start of the period.
+    /// Periods at the edges only count the days within `range`.
+    pub fn aggregate(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+    ) -> Vec<(NaiveDate, usize)> {
+        let activity = activity.within(range);
+        let mut start = self.start(range.from());
+        let mut periods = vec![];
+
+        while start <= range.to() {
+            let next = self.next(start);
+            let count = activity
+                .iter()
+                .filter(|(date, _)| start <= *date && *date < next)
+                .map(|(_, count)| count)
+                .sum();
+            periods.push((start, count));
+            start = next;
+        }
+
+        periods
+    }
+}
+
+/// How the aggregated counts are drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    #[default]
+    Bar,
+    Line,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownChartKindError(pub String);
+
+impl FromStr for ChartKind {
+    type Err = UnknownChartKindError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "bar" => Ok(ChartKind::Bar),
+            "line" => Ok(ChartKind::Line),
+            _ => Err(UnknownChartKindError(name.into())),
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Builder {
+    /// Font, colour and language options, shared with the calendar
+    pub style: svg_renderer::Builder,
+    /// `week` or `month`
+    pub period: Option<String>,
+    /// `bar` or `line`
+    pub kind: Option<String>,
+    /// Draw the average of this many preceding periods as a trend line
+    pub moving_average: Option<usize>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+}
+
+impl Builder {
+    pub fn build(self) -> Result<ChartRenderer, BuilderError> {
+        ChartRenderer::try_from(self)
+    }
+}
+
+impl TryFrom<Builder> for ChartRenderer {
+    type Error = BuilderError;
+
+    fn try_from(value: Builder) -> Result<Self, Self::Error> {
+        let period = match value.period {
+            None => Period::default(),
+            Some(period) => period.parse()?,
+        };
+        let kind = match value.kind {
+            None => ChartKind::default(),
+            Some(kind) => kind.parse()?,
+        };
+        if value.moving_average == Some(0) {
+            return Err(BuilderError::InvalidMovingAverage);
+        }
+
+        Ok(Self {
+            style: value.style.build()?,
+            period,
+            kind,
+            moving_average: value.moving_average,
+            width: value.width.unwrap_or(WIDTH_DEFAULT),
+            height: value.height.unwrap_or(HEIGHT_DEFAULT),
+        })
+    }
+}
+
+/// Draws the contributions per week or month as a bar or line chart
+pub struct ChartRenderer {
+    style: SvgRenderer,
+    period: Period,
+    kind: ChartKind,
+    moving_average: Option<usize>,
+    width: usize,
+    height: usize,
+}
+
+/// The pixel area of the chart within the axis labels
+struct Plot {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    /// The count at the top edge
+    max: usize,
+    /// The horizontal space of each period
+    slot: f64,
+}
+
+impl Plot {
+    fn y(&self, count: f64) -> f64 {
+        self.y + self.height - count / self.max as f64 * self.height
+    }
+
+    /// The horizontal centre of the period at `index`
+    fn x(&self, index: usize) -> f64 {
+        self.x + (index as f64 + 0.5) * self.slot
+    }
+
+    fn polyline(&self, values: impl Iterator<Item = f64>) -> String {
+        values
+            .enumerate()
+            .map(|(index, value)| format!("{:.1},{:.1}", self.x(index), self.y(value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl ChartRenderer {
+    pub fn render(&self, activity: &ContributionActivity, range: &DateRange) -> String {
+        let periods = self.period.aggregate(activity, range);
+        let counts: Vec<usize> = periods.iter().map(|(_, count)| *count).collect();
+        let locale = self.style.locale;
+        let font_size = self.style.font_size as f64;
+
+        let step = tick_step(counts.iter().copied().max().unwrap_or(0));
+        let max = counts
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .div_ceil(step)
+            .max(1)
+            * step;
+        let label_width =
+            (locale.format_number(max).chars().count() as f64 * 0.6 + 1.0) * font_size + PADDING;
+        let x = label_width.max(font_size * 2.0);
+        let width = self.width as f64 - x - PADDING;
+        let plot = Plot {
+            x,
+            y: PADDING + font_size / 2.0,
+            width,
+            height: self.height as f64 - font_size * 2.5 - PADDING * 3.0,
+            max,
+            slot: width / periods.len() as f64,
+        };
+
+        let gridlines = (0..=max).step_by(step).map(|tick| {
+            let y = plot.y(tick as f64);
+            format!(
+                r#"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" class="{}"></line><text x="{:.1}" y="{y:.1}" text-anchor="end" dominant-baseline="central" class="user-contrib-text">{}</text>"#,
+                plot.x,
+                plot.x + plot.width,
+                match tick {
+                    0 => "chart-axis",
+                    _ => "chart-grid",
+                },
+                plot.x - PADDING,
+                locale.format_number(tick)
+            )
+        });
+
+        // Skip labels which would overlap their neighbours
+        let labels_every = (font_size * 3.0 / plot.slot).ceil().max(1.0) as usize;
+        let labels = periods
+            .iter()
+            .enumerate()
+            .step_by(labels_every)
+            .map(|(index, (start, _))| {
+                let text = match self.period {
+                    Period::Week => format!("W{:02}", start.iso_week().week()),
+                    Period::Month => locale.month(start.month0()).to_owned(),
+                };
+                format!(
+                    r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" class="user-contrib-text">{text}</text>"#,
+                    plot.x(index),
+                    plot.y + plot.height + PADDING + font_size
+                )
+            });
+
+        let average_count = counts.iter().sum::<usize>() as f32 / counts.len() as f32;
+        let mut active_counts: Vec<usize> =
+            counts.iter().copied().filter(|count| *count > 0).collect();
+        active_counts.sort();
+        let info = |count| ContributionInfo {
+            average_count_per_day: average_count,
+            count_today: count,
+            active_counts: &active_counts,
+        };
+        let fill = |count| {
+            let dark_fill = match &self.style.dark {
+                None => String::new(),
+                Some(palette) => format!(
+                    r#" style="--dark-fill: {}""#,
+                    palette.colour_strategy.get_colour(info(count))
+                ),
+            };
+            format!(
+                r#"fill="{}"{dark_fill}"#,
+                self.style.colour_strategy.get_colour(info(count))
+            )
+        };
+
+        let marks = periods.iter().enumerate().map(|(index, (start, count))| {
+            let title = format!(
+                "{}: {}",
+                self.period.key(*start),
+                capitalise(&locale.describe_count(*count))
+            );
+            let (x, y) = (plot.x(index), plot.y(*count as f64));
+            match self.kind {
+                ChartKind::Bar => format!(
+                    r#"<rect x="{:.1}" y="{y:.1}" width="{:.1}" height="{:.1}" {} data-period="{}" class="chart-bar"><title>{title}</title></rect>"#,
+                    x - plot.slot * 0.4,
+                    plot.slot * 0.8,
+                    plot.y + plot.height - y,
+                    fill(*count),
+                    self.period.key(*start)
+                ),
+                ChartKind::Line => format!(
+                    r#"<circle cx="{x:.1}" cy="{y:.1}" r="3" {} data-period="{}" class="chart-point"><title>{title}</title></circle>"#,
+                    fill(*count),
+                    self.period.key(*start)
+                ),
+            }
+        });
+
+        let line = match self.kind {
+            ChartKind::Bar => None,
+            ChartKind::Line => {
+                let strongest = self.style.colour_strategy.legend().pop();
+                let dark_stroke = match &self.style.dark {
+                    None => String::new(),
+                    Some(palette) => match palette.colour_strategy.legend().pop() {
+                        None => String::new(),
+                        Some(colour) => format!(r#" style="--dark-stroke: {colour}""#),
+                    },
+                };
+                Some(format!(
+                    r#"<polyline points="{}" stroke="{}"{dark_stroke} class="chart-line"></polyline>"#,
+                    plot.polyline(counts.iter().map(|count| *count as f64)),
+                    strongest.unwrap_or(self.style.font_colour.clone())
+                ))
+            }
+        };
+
+        let average = self.moving_average.map(|window| {
+            format!(
+                r#"<polyline points="{}" class="chart-average"></polyline>"#,
+                plot.polyline(moving_average(&counts, window).into_iter())
+            )
+        });
+
+        let content = gridlines
+            .chain(labels)
+            .chain(line)
+            .chain(marks)
+            .chain(average)
+            .collect::<Vec<_>>()
+            .join("\n    ");
+        let summary = summarise(activity, range, locale);
+
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" role="img" aria-label="{}" class="contrib-chart" data-testid="contrib-chart">
+    <title>{}</title>
+    <desc>{}</desc>
+    {}
+    {content}
+</svg>"#,
+            self.width,
+            self.height,
+            summary.label,
+            summary.title,
+            summary.description,
+            self.get_style(),
+        )
+    }
+
+    fn get_style(&self) -> String {
+        let dark = match &self.style.dark {
+            None => String::new(),
+            Some(palette) => format!(
+                r#"
+
+            @media (prefers-color-scheme: dark) {{
+                .user-contrib-text {{
+                    fill: {0};
+                }}
+
+                .chart-grid, .chart-axis, .chart-average {{
+                    stroke: {0};
+                }}
+
+                .chart-bar, .chart-point {{
+                    fill: var(--dark-fill);
+                }}
+
+                .chart-line {{
+                    stroke: var(--dark-stroke);
+                }}
+            }}"#,
+                palette.font_colour
+            ),
+        };
+
+        format!(
+            r#"<style>
+            .user-contrib-text {{
+                font-size: {}px;
+                font-family: "Noto Sans", Ubuntu, Cantarell, "Helvetica Neue", sans-serif;
+                fill: {1};
+            }}
+
+            .chart-grid, .chart-axis {{
+                stroke: {1};
+                stroke-width: 1;
+            }}
+
+            .chart-grid {{
+                stroke-opacity: 0.15;
+            }}
+
+            .chart-line, .chart-average {{
+                fill: none;
+                stroke-width: 2;
+                stroke-linejoin: round;
+            }}
+
+            .chart-average {{
+                stroke: {1};
+                stroke-dasharray: 4 3;
+            }}{dark}
+        </style>"#,
+            self.style.font_size, self.style.font_colour,
+        )
+    }
+}
+
+/// A round distance between gridlines, such that about `TICKS` of them cover `max`
+fn tick_step(max: usize) -> usize {
+    let rough = max.div_ceil(TICKS).max(1);
+    let magnitude = 10_usize.pow(rough.ilog10());
+    [1, 2, 5, 10]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10 * magnitude)
+}
+
+/// The average of each count and up to `window - 1` preceding ones
+fn moving_average(counts: &[usize], window: usize) -> Vec<f64> {
+    (0..counts.len())
+        .map(|index| {
+            let values = &counts[(index + 1).saturating_sub(window)..=index];
+            values.iter().sum::<usize>() as f64 / values.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{moving_average, tick_step, Builder, Period};
+    use crate::{
+        svg::svg_renderer::{self, BuilderError},
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn activity() -> ContributionActivity {
+        ContributionActivity::from(BTreeMap::from([
+            (date(1, 1), 3),
+            (date(1, 7), 2),
+            (date(1, 8), 5),
+            (date(2, 29), 4),
+            (date(3, 31), 1),
+        ]))
+    }
+
+    #[test]
+    fn aggregate() {
+        let range = DateRange::new(date(1, 3), date(3, 31)).unwrap();
+        let months = Period::Month.aggregate(&activity(), &range);
+        assert_eq!(
+            months,
+            vec![(date(1, 1), 7), (date(2, 1), 4), (date(3, 1), 1)]
+        );
+
+        let weeks = Period::Week.aggregate(&activity(), &range);
+        assert_eq!(weeks.len(), 13);
+        assert_eq!(weeks[0], (date(1, 1), 2));
+        assert_eq!(weeks[1], (date(1, 8), 5));
+        assert_eq!(weeks[12], (date(3, 25), 1));
+        assert_eq!(Period::Week.key(weeks[1].0), "2024-W02");
+    }
+
+    #[test]
+    fn bar_chart() {
+        let svg = Builder {
+            period: Some("month".into()),
+            style: svg_renderer::Builder {
+                locale: Some("de".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .render(
+            &activity(),
+            &DateRange::new(date(1, 1), date(3, 31)).unwrap(),
+        );
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches(r#"class="chart-bar""#).count(), 3);
+        assert!(svg.contains(
+            r#"data-period="2024-01" class="chart-bar"><title>2024-01: 10 Beiträge</title>"#
+        ));
+        assert!(svg.contains(">Feb</text>"));
+        assert_eq!(svg.matches(r#"class="chart-grid""#).count(), 2);
+        assert!(!svg.contains(r#"class="chart-average""#));
+    }
+
+    #[test]
+    fn line_chart() {
+        let svg = Builder {
+            kind: Some("line".into()),
+            moving_average: Some(4),
+            style: svg_renderer::Builder {
+                dark_theme: Some("github-dark".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .render(
+            &activity(),
+            &DateRange::new(date(1, 1), date(3, 31)).unwrap(),
+        );
+
+        assert_eq!(svg.matches(r#"class="chart-point""#).count(), 13);
+        assert_eq!(svg.matches(r#"class="chart-line""#).count(), 1);
+        assert_eq!(svg.matches(r#"class="chart-average""#).count(), 1);
+        assert!(svg.contains(">W01</text>"));
+        assert!(svg.contains("--dark-stroke: #"));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let build = |builder: Builder| builder.build().err();
+        assert_eq!(
+            build(Builder {
+                period: Some("fortnight".into()),
+                ..Default::default()
+            }),
+            Some(BuilderError::UnknownPeriod("fortnight".into()))
+        );
+        assert_eq!(
+            build(Builder {
+                kind: Some("pie".into()),
+                ..Default::default()
+            }),
+            Some(BuilderError::UnknownChartKind("pie".into()))
+        );
+        assert_eq!(
+            build(Builder {
+                moving_average: Some(0),
+                ..Default::default()
+            }),
+            Some(BuilderError::InvalidMovingAverage)
+        );
+    }
+
+    #[test]
+    fn ticks() {
+        assert_eq!(tick_step(0), 1);
+        assert_eq!(tick_step(3), 1);
+        assert_eq!(tick_step(10), 5);
+        assert_eq!(tick_step(37), 10);
+        assert_eq!(tick_step(1234), 500);
+        assert_eq!(moving_average(&[2, 4, 6, 8], 2), vec![2.0, 3.0, 5.0, 7.0]);
+    }
+}
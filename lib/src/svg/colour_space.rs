@@ -0,0 +1,188 @@
+use std::{f64::consts::PI, str::FromStr};
+
+use serde::Deserialize;
+
+/// The colour space in which colours are interpolated
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ColourSpace {
+    /// Interpolate the gamma encoded sRGB channels directly
+    #[default]
+    Srgb,
+    /// Interpolate physical light intensities, avoiding dark midpoints
+    LinearRgb,
+    /// Interpolate in the perceptually uniform OKLab space
+    Oklab,
+    /// Interpolate lightness, chroma and hue of OKLab, keeping midpoints saturated
+    Oklch,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownColourSpaceError(pub String);
+
+impl FromStr for ColourSpace {
+    type Err = UnknownColourSpaceError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "srgb" => Ok(ColourSpace::Srgb),
+            "linear-rgb" | "linearrgb" => Ok(ColourSpace::LinearRgb),
+            "oklab" => Ok(ColourSpace::Oklab),
+            "oklch" => Ok(ColourSpace::Oklch),
+            _ => Err(UnknownColourSpaceError(name.into())),
+        }
+    }
+}
+
+impl ColourSpace {
+    /// Interpolate between two sRGB colours with channels ranging from 0 to 1
+    pub(crate) fn interpolate(&self, from: [f64; 3], to: [f64; 3], factor: f64) -> [f64; 3] {
+        let rgb = match self {
+            ColourSpace::Srgb => lerp(from, to, factor),
+            ColourSpace::LinearRgb => to_srgb(lerp(to_linear(from), to_linear(to), factor)),
+            ColourSpace::Oklab => {
+                oklab_to_srgb(lerp(srgb_to_oklab(from), srgb_to_oklab(to), factor))
+            }
+            ColourSpace::Oklch => {
+                let from = oklab_to_oklch(srgb_to_oklab(from));
+                let to = oklab_to_oklch(srgb_to_oklab(to));
+                oklab_to_srgb(oklch_to_oklab(lerp_lch(from, to, factor)))
+            }
+        };
+        rgb.map(|c| c.clamp(0.0, 1.0))
+    }
+}
+
+fn lerp(from: [f64; 3], to: [f64; 3], factor: f64) -> [f64; 3] {
+    [0, 1, 2].map(|i| from[i] + factor * (to[i] - from[i]))
+}
+
+/// Interpolate along the shorter arc of the hue circle.
+/// Achromatic colours have no meaningful hue, so the hue of the other colour is used.
+fn lerp_lch(from: [f64; 3], to: [f64; 3], factor: f64) -> [f64; 3] {
+    const ACHROMATIC: f64 = 1e-4;
+    let (from_hue, to_hue) = match (from[1] < ACHROMATIC, to[1] < ACHROMATIC) {
+        (true, false) => (to[2], to[2]),
+        (false, true) => (from[2], from[2]),
+        _ => (from[2], to[2]),
+    };
+
+    let mut delta = to_hue - from_hue;
+    if delta > PI {
+        delta -= 2.0 * PI;
+    } else if delta < -PI {
+        delta += 2.0 * PI;
+    }
+
+    [
+        from[0] + factor * (to[0] - from[0]),
+        from[1] + factor * (to[1] - from[1]),
+        from_hue + factor * delta,
+    ]
+}
+
+fn to_linear(rgb: [f64; 3]) -> [f64; 3] {
+    rgb.map(|c| match c <= 0.04045 {
+        true => c / 12.92,
+        false => ((c + 0.055) / 1.055).powf(2.4),
+    })
+}
+
+fn to_srgb(rgb: [f64; 3]) -> [f64; 3] {
+    rgb.map(|c| match c <= 0.0031308 {
+        true => c * 12.92,
+        false => 1.055 * c.max(0.0).powf(1.0 / 2.4) - 0.055,
+    })
+}
+
+/// See <https://bottosson.github.io/posts/oklab/>
+fn srgb_to_oklab(rgb: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = to_linear(rgb);
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+fn oklab_to_srgb([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    to_srgb([
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ])
+}
+
+fn oklab_to_oklch([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+    [lightness, a.hypot(b), b.atan2(a)]
+}
+
+fn oklch_to_oklab([lightness, chroma, hue]: [f64; 3]) -> [f64; 3] {
+    [lightness, chroma * hue.cos(), chroma * hue.sin()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{srgb_to_oklab, ColourSpace};
+
+    const SPACES: [ColourSpace; 4] = [
+        ColourSpace::Srgb,
+        ColourSpace::LinearRgb,
+        ColourSpace::Oklab,
+        ColourSpace::Oklch,
+    ];
+
+    fn assert_close(actual: [f64; 3], expected: [f64; 3]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-3, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn endpoints() {
+        let from = [0.9, 0.8, 0.1];
+        let to = [0.4, 0.1, 0.6];
+        for space in SPACES {
+            assert_close(space.interpolate(from, to, 0.0), from);
+            assert_close(space.interpolate(from, to, 1.0), to);
+        }
+    }
+
+    #[test]
+    fn oklab_white() {
+        assert_close(srgb_to_oklab([1.0, 1.0, 1.0]), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn midpoints() {
+        let black = [0.0; 3];
+        let white = [1.0; 3];
+        assert_close(ColourSpace::Srgb.interpolate(black, white, 0.5), [0.5; 3]);
+        assert_close(
+            ColourSpace::LinearRgb.interpolate(black, white, 0.5),
+            [0.7354; 3],
+        );
+        assert_close(
+            ColourSpace::Oklab.interpolate(black, white, 0.5),
+            [0.3885; 3],
+        );
+        assert_close(
+            ColourSpace::Oklch.interpolate(black, white, 0.5),
+            [0.3885; 3],
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("OKLCH".parse(), Ok(ColourSpace::Oklch));
+        assert_eq!("linear-rgb".parse(), Ok(ColourSpace::LinearRgb));
+        assert!("hsl".parse::<ColourSpace>().is_err());
+    }
+}
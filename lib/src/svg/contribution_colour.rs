@@ -1,11 +1,16 @@
+use std::str::FromStr;
+
 use serde::Deserialize;
 
-use super::rgba::Rgba;
+use super::{colour_space::ColourSpace, rgba::Rgba};
+use crate::statistics::percentile;
 
 /// Information statistics used for calculating the cell colour of a day.
-pub(crate) struct ContributionInfo {
+pub(crate) struct ContributionInfo<'a> {
     pub(crate) average_count_per_day: f32,
     pub(crate) count_today: usize,
+    /// Contribution counts of all active days, sorted ascendingly
+    pub(crate) active_counts: &'a [usize],
 }
 
 /// The different strategies to calculate the colour of a cell
@@ -13,21 +18,146 @@ pub(crate) struct ContributionInfo {
 pub enum ColourStrategy {
     /// The way GitLab visualises contribution activity
     GitlabStrategy,
-    /// Smoothly interpolate from `inactive_colour` to `active_colour`
+    /// Smoothly interpolate from `inactive_colour` over the `intermediate_colours` to `active_colour`
     InterpolationStrategy {
         inactive_colour: Rgba,
         active_colour: Rgba,
+        #[serde(default)]
+        intermediate_colours: Vec<Rgba>,
+        #[serde(default)]
+        colour_space: ColourSpace,
+        #[serde(default)]
+        scale: Scale,
+    },
+    /// Pick one of `colours`, where `thresholds` holds the minimum contribution count
+    /// of every colour after the first one in ascending order
+    DiscreteStrategy {
+        colours: Vec<Rgba>,
+        thresholds: Vec<usize>,
     },
+    /// Pick one of `colours`, using the first one for days without contributions
+    /// and splitting the active days into equally sized buckets for the remaining ones.
+    /// With five colours this assigns the quartiles of the active days, like GitHub does.
+    QuantileStrategy { colours: Vec<Rgba> },
 }
 
 impl ColourStrategy {
     pub(crate) fn get_colour(&self, info: ContributionInfo) -> Rgba {
         match self {
             ColourStrategy::GitlabStrategy => get_gitlab_colour(info),
-            ColourStrategy::InterpolationStrategy {
-                inactive_colour,
-                active_colour,
-            } => get_interpolated_colour(inactive_colour, active_colour, info),
+            ColourStrategy::InterpolationStrategy { scale, .. } => {
+                self.gradient(scale.apply(&info))
+            }
+            ColourStrategy::DiscreteStrategy {
+                colours,
+                thresholds,
+            } => get_discrete_colour(colours, thresholds, info),
+            ColourStrategy::QuantileStrategy { colours } => get_quantile_colour(colours, info),
+        }
+    }
+
+    /// A few representative colours from least to most active, as shown in a legend
+    pub(crate) fn legend(&self) -> Vec<Rgba> {
+        match self {
+            ColourStrategy::GitlabStrategy => [0, 1, 10, 20, 30]
+                .into_iter()
+                .map(|count_today| {
+                    get_gitlab_colour(ContributionInfo {
+                        average_count_per_day: 0.0,
+                        count_today,
+                        active_counts: &[],
+                    })
+                })
+                .collect(),
+            ColourStrategy::InterpolationStrategy { .. } => [0.0, 0.25, 0.5, 0.75, 1.0]
+                .into_iter()
+                .map(|factor| self.gradient(factor))
+                .collect(),
+            ColourStrategy::DiscreteStrategy { colours, .. }
+            | ColourStrategy::QuantileStrategy { colours } => colours.clone(),
+        }
+    }
+
+    /// The colour at `factor`, ranging from 0 to 1, of the gradient of an `InterpolationStrategy`
+    fn gradient(&self, factor: f32) -> Rgba {
+        let ColourStrategy::InterpolationStrategy {
+            inactive_colour,
+            active_colour,
+            intermediate_colours,
+            colour_space,
+            ..
+        } = self
+        else {
+            panic!("only interpolation strategies have a gradient");
+        };
+
+        let stops: Vec<&Rgba> = std::iter::once(inactive_colour)
+            .chain(intermediate_colours)
+            .chain(std::iter::once(active_colour))
+            .collect();
+
+        let position = factor.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let segment = (position as usize).min(stops.len() - 2);
+        stops[segment].interpolate_in(stops[segment + 1], position - segment as f32, *colour_space)
+    }
+}
+
+/// How the contribution count of a day is mapped onto the gradient of an `InterpolationStrategy`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Scale {
+    /// Relative to the average contribution count, see `f`
+    #[default]
+    Average,
+    /// Proportional to the highest contribution count
+    Linear,
+    /// Logarithmic relative to the highest contribution count, so a single huge day doesn't wash out the rest
+    Logarithmic,
+    /// Square root relative to the highest contribution count
+    SquareRoot,
+    /// The share of active days with at most as many contributions
+    PercentileRank,
+}
+
+impl Scale {
+    /// Map the contribution count of the day onto a number ranging from 0 to 1
+    fn apply(&self, info: &ContributionInfo) -> f32 {
+        let x = info.count_today as f32;
+        let max = info.active_counts.last().copied().unwrap_or(0) as f32;
+        let relative = |scale: fn(f32) -> f32| match max {
+            0.0 => 0.0,
+            _ => scale(x) / scale(max),
+        };
+
+        match self {
+            Scale::Average => f(x, info.average_count_per_day),
+            Scale::Linear => relative(|x| x),
+            Scale::Logarithmic => relative(f32::ln_1p),
+            Scale::SquareRoot => relative(f32::sqrt),
+            Scale::PercentileRank => match (info.count_today, info.active_counts.len()) {
+                (0, _) | (_, 0) => 0.0,
+                (count, active_days) => {
+                    info.active_counts.partition_point(|c| *c <= count) as f32 / active_days as f32
+                }
+            },
+        }
+        .min(1.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownScaleError(pub String);
+
+impl FromStr for Scale {
+    type Err = UnknownScaleError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "average" => Ok(Scale::Average),
+            "linear" => Ok(Scale::Linear),
+            "logarithmic" | "log" => Ok(Scale::Logarithmic),
+            "square-root" | "sqrt" => Ok(Scale::SquareRoot),
+            "percentile-rank" => Ok(Scale::PercentileRank),
+            _ => Err(UnknownScaleError(name.into())),
         }
     }
 }
@@ -61,20 +191,32 @@ fn get_gitlab_colour(info: ContributionInfo) -> Rgba {
     }
 }
 
-fn get_interpolated_colour(
-    inactive_colour: &Rgba,
-    active_colour: &Rgba,
-    info: ContributionInfo,
-) -> Rgba {
-    inactive_colour.interpolate(
-        active_colour.clone(),
-        f(info.count_today as f32, info.average_count_per_day),
-    )
+fn get_discrete_colour(colours: &[Rgba], thresholds: &[usize], info: ContributionInfo) -> Rgba {
+    let index = thresholds
+        .iter()
+        .take_while(|threshold| info.count_today >= **threshold)
+        .count();
+    colours[index.min(colours.len() - 1)].clone()
+}
+
+fn get_quantile_colour(colours: &[Rgba], info: ContributionInfo) -> Rgba {
+    let buckets = colours.len() - 1;
+    let index = match info.count_today {
+        0 => 0,
+        count => {
+            1 + (1..buckets)
+                .filter(|bucket| count > percentile(info.active_counts, bucket * 100 / buckets))
+                .count()
+        }
+    };
+    colours[index].clone()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ColourStrategy::InterpolationStrategy;
+    use super::ColourStrategy::{DiscreteStrategy, InterpolationStrategy, QuantileStrategy};
+    use super::Scale;
+    use crate::svg::colour_space::ColourSpace;
     use crate::svg::contribution_colour::ContributionInfo;
     use crate::svg::rgba::Rgba;
 
@@ -86,6 +228,9 @@ mod tests {
         let style = InterpolationStrategy {
             active_colour: ACTIVE,
             inactive_colour: INACTIVE,
+            intermediate_colours: vec![],
+            colour_space: ColourSpace::Srgb,
+            scale: Scale::Average,
         };
 
         let average_count_per_day = 5.0;
@@ -93,6 +238,7 @@ mod tests {
             style.get_colour(ContributionInfo {
                 average_count_per_day,
                 count_today: 0,
+                active_counts: &[],
             }),
             INACTIVE
         );
@@ -101,6 +247,7 @@ mod tests {
             style.get_colour(ContributionInfo {
                 average_count_per_day,
                 count_today: 999999,
+                active_counts: &[],
             }),
             ACTIVE
         );
@@ -109,8 +256,113 @@ mod tests {
             style.get_colour(ContributionInfo {
                 average_count_per_day: 0.0,
                 count_today: 0,
+                active_counts: &[],
             }),
             INACTIVE
         );
     }
+
+    #[test]
+    fn gradient() {
+        const RED: Rgba = Rgba::new(255, 0, 0, 255);
+        const GREEN: Rgba = Rgba::new(0, 255, 0, 255);
+        const BLUE: Rgba = Rgba::new(0, 0, 255, 255);
+
+        let style = InterpolationStrategy {
+            inactive_colour: RED,
+            active_colour: BLUE,
+            intermediate_colours: vec![GREEN],
+            colour_space: ColourSpace::Srgb,
+            scale: Scale::Average,
+        };
+        assert_eq!(style.legend()[0], RED);
+        assert_eq!(style.legend()[1], Rgba::new(128, 128, 0, 255));
+        assert_eq!(style.legend()[2], GREEN);
+        assert_eq!(style.legend()[4], BLUE);
+
+        let style = InterpolationStrategy {
+            inactive_colour: RED,
+            active_colour: BLUE,
+            intermediate_colours: vec![],
+            colour_space: ColourSpace::Oklch,
+            scale: Scale::Average,
+        };
+        assert_eq!(style.legend()[0], RED);
+        assert_eq!(style.legend()[4], BLUE);
+        assert_ne!(style.legend()[2], RED.interpolate(BLUE, 0.5));
+    }
+
+    #[test]
+    fn scales() {
+        let active_counts = [1, 2, 2, 4, 100];
+        let apply = |scale: Scale, count_today| {
+            scale.apply(&ContributionInfo {
+                average_count_per_day: 109.0 / 365.0,
+                count_today,
+                active_counts: &active_counts,
+            })
+        };
+
+        for scale in [
+            Scale::Average,
+            Scale::Linear,
+            Scale::Logarithmic,
+            Scale::SquareRoot,
+            Scale::PercentileRank,
+        ] {
+            assert_eq!(apply(scale, 0), 0.0);
+            assert!((apply(scale, 100) - 1.0).abs() < 0.01);
+        }
+
+        assert_eq!(apply(Scale::Linear, 2), 0.02);
+        assert_eq!(apply(Scale::SquareRoot, 4), 0.2);
+        assert_eq!(apply(Scale::PercentileRank, 2), 0.6);
+        assert!(apply(Scale::Logarithmic, 4) > 0.3);
+        assert_eq!("sqrt".parse(), Ok(Scale::SquareRoot));
+    }
+
+    #[test]
+    fn discrete() {
+        let colours: Vec<Rgba> = (0..4).map(|i| Rgba::new(i, i, i, 255)).collect();
+        let style = DiscreteStrategy {
+            colours: colours.clone(),
+            thresholds: vec![1, 5, 10],
+        };
+        let colour = |count_today| {
+            style.get_colour(ContributionInfo {
+                average_count_per_day: 0.0,
+                count_today,
+                active_counts: &[],
+            })
+        };
+
+        assert_eq!(colour(0), colours[0]);
+        assert_eq!(colour(4), colours[1]);
+        assert_eq!(colour(5), colours[2]);
+        assert_eq!(colour(100), colours[3]);
+    }
+
+    #[test]
+    fn quantile() {
+        let colours: Vec<Rgba> = (0..5).map(|i| Rgba::new(i, i, i, 255)).collect();
+        let style = QuantileStrategy {
+            colours: colours.clone(),
+        };
+        let active_counts = [1, 1, 2, 2, 3, 4, 6, 8];
+        let colour = |count_today| {
+            style.get_colour(ContributionInfo {
+                average_count_per_day: 0.0,
+                count_today,
+                active_counts: &active_counts,
+            })
+        };
+
+        assert_eq!(colour(0), colours[0]);
+        assert_eq!(colour(1), colours[1]);
+        assert_eq!(colour(2), colours[2]);
+        assert_eq!(colour(3), colours[3]);
+        assert_eq!(colour(4), colours[3]);
+        assert_eq!(colour(5), colours[4]);
+        assert_eq!(colour(8), colours[4]);
+    }
 }
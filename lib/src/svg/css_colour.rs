@@ -0,0 +1,370 @@
+use super::rgba::{Rgba, StringToRgbaError};
+
+/// The named colours of CSS Color Level 4, sorted by name
+const NAMED_COLOURS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+/// Look up a named colour such as `rebeccapurple` or `transparent`
+pub(crate) fn named_colour(name: &str) -> Option<Rgba> {
+    if name == "transparent" {
+        return Some(Rgba::new(0, 0, 0, 0));
+    }
+
+    let index = NAMED_COLOURS
+        .binary_search_by(|(candidate, _)| candidate.cmp(&name))
+        .ok()?;
+    let [_, r, g, b] = NAMED_COLOURS[index].1.to_be_bytes();
+    Some(Rgba::new(r, g, b, 255))
+}
+
+/// Parse one of the colour functions `rgb()`, `rgba()`, `hsl()`, `hsla()` or `hwb()`,
+/// in both the modern space separated and the legacy comma separated syntax
+pub(crate) fn colour_function(value: &str) -> Result<Rgba, StringToRgbaError> {
+    use StringToRgbaError::*;
+
+    let (name, arguments) = value
+        .strip_suffix(')')
+        .and_then(|value| value.split_once('('))
+        .ok_or(MissingParenthesis)?;
+    let name = name.trim();
+
+    let (channels, alpha): (Vec<&str>, Option<&str>) = match arguments.contains(',') {
+        true if name == "hwb" => Err(UnexpectedCommas)?,
+        true => {
+            let mut arguments: Vec<&str> = arguments.split(',').map(str::trim).collect();
+            let alpha = (arguments.len() == 4).then(|| arguments.pop()).flatten();
+            (arguments, alpha)
+        }
+        false => {
+            let (channels, alpha) = match arguments.split_once('/') {
+                Some((channels, alpha)) => (channels, Some(alpha.trim())),
+                None => (arguments, None),
+            };
+            (channels.split_whitespace().collect(), alpha)
+        }
+    };
+
+    let [first, second, third] = channels[..] else {
+        return Err(InvalidArgumentCount(channels.len()));
+    };
+
+    let alpha = match alpha {
+        None => 255,
+        Some(alpha) => (number_or_percentage(alpha, 1.0)? * 255.0).round() as u8,
+    };
+
+    let [r, g, b] = match name {
+        "rgb" | "rgba" => {
+            [first, second, third].map(|channel| number_or_percentage(channel, 255.0))
+        }
+        "hsl" | "hsla" => {
+            let rgb = hsl_to_rgb(
+                hue(first)?,
+                number_or_percentage(second, 100.0)? / 100.0,
+                number_or_percentage(third, 100.0)? / 100.0,
+            );
+            rgb.map(|channel| Ok(channel * 255.0))
+        }
+        "hwb" => {
+            let rgb = hwb_to_rgb(
+                hue(first)?,
+                number_or_percentage(second, 100.0)? / 100.0,
+                number_or_percentage(third, 100.0)? / 100.0,
+            );
+            rgb.map(|channel| Ok(channel * 255.0))
+        }
+        _ => return Err(UnknownFunction(name.into())),
+    }
+    .map(|channel| channel.map(|channel| channel.round() as u8));
+
+    Ok(Rgba::new(r?, g?, b?, alpha))
+}
+
+/// Parse a number or a percentage of `max`, clamped to the range from 0 to `max` as CSS does.
+/// The keyword `none` represents a missing component and is treated as 0.
+fn number_or_percentage(value: &str, max: f32) -> Result<f32, StringToRgbaError> {
+    let number = match value.strip_suffix('%') {
+        _ if value == "none" => 0.0,
+        Some(percentage) => parse_number(percentage)? / 100.0 * max,
+        None => parse_number(value)?,
+    };
+    Ok(number.clamp(0.0, max))
+}
+
+/// Parse an angle in degrees, which can have one of the units `deg`, `rad`, `grad` or `turn`
+fn hue(value: &str) -> Result<f32, StringToRgbaError> {
+    let degrees = if value == "none" {
+        0.0
+    } else if let Some(value) = value.strip_suffix("deg") {
+        parse_number(value)?
+    } else if let Some(value) = value.strip_suffix("grad") {
+        parse_number(value)? * 0.9
+    } else if let Some(value) = value.strip_suffix("rad") {
+        parse_number(value)?.to_degrees()
+    } else if let Some(value) = value.strip_suffix("turn") {
+        parse_number(value)? * 360.0
+    } else {
+        parse_number(value)?
+    };
+    Ok(degrees.rem_euclid(360.0))
+}
+
+fn parse_number(value: &str) -> Result<f32, StringToRgbaError> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| StringToRgbaError::InvalidNumber(value.into()))
+}
+
+/// See <https://www.w3.org/TR/css-color-4/#hsl-to-rgb>
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> [f32; 3] {
+    let f = |n: f32| {
+        let k = (n + hue / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+/// See <https://www.w3.org/TR/css-color-4/#hwb-to-rgb>
+fn hwb_to_rgb(hue: f32, whiteness: f32, blackness: f32) -> [f32; 3] {
+    if whiteness + blackness >= 1.0 {
+        let grey = whiteness / (whiteness + blackness);
+        return [grey; 3];
+    }
+    hsl_to_rgb(hue, 1.0, 0.5).map(|channel| channel * (1.0 - whiteness - blackness) + whiteness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{colour_function, named_colour, NAMED_COLOURS};
+    use crate::svg::rgba::{Rgba, StringToRgbaError};
+
+    #[test]
+    fn named_colours_are_sorted() {
+        assert!(NAMED_COLOURS.is_sorted_by_key(|(name, _)| *name));
+    }
+
+    #[test]
+    fn named() {
+        assert_eq!(
+            named_colour("rebeccapurple"),
+            Some(Rgba::new(102, 51, 153, 255))
+        );
+        assert_eq!(named_colour("transparent"), Some(Rgba::new(0, 0, 0, 0)));
+        assert_eq!(named_colour("reddish"), None);
+    }
+
+    #[test]
+    fn rgb() {
+        let expected = Ok(Rgba::new(10, 20, 30, 128));
+        assert_eq!(colour_function("rgb(10 20 30 / 50%)"), expected);
+        assert_eq!(colour_function("rgb(10 20 30 / 0.5)"), expected);
+        assert_eq!(colour_function("rgba(10, 20, 30, 0.5)"), expected);
+        assert_eq!(
+            colour_function("rgb(100% 0% 50%)"),
+            Ok(Rgba::new(255, 0, 128, 255))
+        );
+        assert_eq!(
+            colour_function("rgb(300 -5 none)"),
+            Ok(Rgba::new(255, 0, 0, 255))
+        );
+    }
+
+    #[test]
+    fn hsl() {
+        let expected = Ok(Rgba::new(255, 0, 0, 255));
+        assert_eq!(colour_function("hsl(0 100% 50%)"), expected);
+        assert_eq!(colour_function("hsl(360deg, 100%, 50%)"), expected);
+        assert_eq!(colour_function("hsl(1turn 100 50)"), expected);
+        assert_eq!(
+            colour_function("hsla(120, 100%, 25%, 1)"),
+            Ok(Rgba::new(0, 128, 0, 255))
+        );
+        assert_eq!(
+            colour_function("hsl(0.5turn 0% 100% / 0)"),
+            Ok(Rgba::new(255, 255, 255, 0))
+        );
+    }
+
+    #[test]
+    fn hwb() {
+        assert_eq!(
+            colour_function("hwb(240 0% 0%)"),
+            Ok(Rgba::new(0, 0, 255, 255))
+        );
+        assert_eq!(
+            colour_function("hwb(0 60% 60%)"),
+            Ok(Rgba::new(128, 128, 128, 255))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        use StringToRgbaError::*;
+        assert_eq!(colour_function("rgb(1 2 3"), Err(MissingParenthesis));
+        assert_eq!(colour_function("rgb(1 2)"), Err(InvalidArgumentCount(2)));
+        assert_eq!(
+            colour_function("rgb(1 2 3 4)"),
+            Err(InvalidArgumentCount(4))
+        );
+        assert_eq!(
+            colour_function("rgb(1 x 3)"),
+            Err(InvalidNumber("x".into()))
+        );
+        assert_eq!(
+            colour_function("lab(50% 40 59)"),
+            Err(UnknownFunction("lab".into()))
+        );
+        assert_eq!(colour_function("hwb(0, 10%, 10%)"), Err(UnexpectedCommas));
+    }
+}
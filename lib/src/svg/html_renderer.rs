@@ -0,0 +1,369 @@
+use super::layout::{
+    Anchor, Annotation, AnnotationKind, Baseline, CalendarLayout, CellShape, Day, Label,
+    SourceSplit,
+};
+use super::renderer::{escape, Renderer};
+use super::rgba::Rgba;
+
+/// Renders a standalone HTML page, positioning the cells and labels with CSS
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String {
+        let labels = layout
+            .caption
+            .iter()
+            .chain(layout.calendars.iter().flat_map(|calendar| {
+                calendar
+                    .title
+                    .iter()
+                    .chain(&calendar.weekday_labels)
+                    .chain(&calendar.month_labels)
+            }))
+            .chain(
+                layout
+                    .legend
+                    .iter()
+                    .flat_map(|legend| [&legend.less, &legend.more]),
+            )
+            .chain(
+                layout
+                    .source_legend
+                    .iter()
+                    .flat_map(|legend| legend.entries.iter().map(|(_, label)| label)),
+            )
+            .map(|label| render_label(label, layout));
+
+        let cells = layout.days().map(|day| {
+            format!(
+                r#"<div class="user-contrib-cell" style="left: {}px; top: {}px; {}" title="{}" data-date="{}" data-count="{}"></div>"#,
+                day.x,
+                day.y,
+                match source_gradient(day, layout) {
+                    None => fill(&day.colour, day.dark_colour.as_ref()),
+                    Some(gradient) => format!("--fill: {gradient}; --dark-fill: {gradient}"),
+                },
+                escape(&day.tooltip),
+                day.date,
+                day.count
+            )
+        });
+
+        let legend_cells = layout
+            .legend
+            .iter()
+            .flat_map(|legend| &legend.cells)
+            .chain(
+                layout
+                    .source_legend
+                    .iter()
+                    .flat_map(|legend| legend.entries.iter().map(|(cell, _)| cell)),
+            )
+            .map(|cell| {
+                format!(
+                    r#"<div class="user-contrib-legend-cell" style="left: {}px; top: {}px; {}"></div>"#,
+                    cell.x,
+                    cell.y,
+                    fill(&cell.colour, cell.dark_colour.as_ref())
+                )
+            });
+
+        let annotations = layout
+            .calendars
+            .iter()
+            .flat_map(|calendar| &calendar.annotations)
+            .flat_map(|annotation| render_annotation(annotation, layout));
+
+        let content = labels
+            .chain(cells)
+            .chain(annotations)
+            .chain(legend_cells)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{}" dir="{}">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+{}
+</head>
+<body>
+<div class="contrib-calendar" role="img" aria-label="{}" data-testid="contrib-calendar">
+{content}
+</div>
+<p class="contrib-calendar-description">{}</p>
+</body>
+</html>"#,
+            layout.language,
+            layout.direction,
+            layout.summary.title,
+            get_style(layout),
+            layout.summary.label,
+            layout.summary.description
+        )
+    }
+}
+
+fn fill(colour: &Rgba, dark_colour: Option<&Rgba>) -> String {
+    match dark_colour {
+        None => format!("--fill: {colour}"),
+        Some(dark_colour) => format!("--fill: {colour}; --dark-fill: {dark_colour}"),
+    }
+}
+
+/// A gradient with hard stops dividing the cell of an active day between its sources
+fn source_gradient(day: &Day, layout: &CalendarLayout) -> Option<String> {
+    if day.count == 0 || day.source_counts.is_empty() {
+        return None;
+    }
+
+    let direction = match layout.source_split {
+        SourceSplit::Stacked => "to bottom",
+        SourceSplit::Stripes => "to right",
+    };
+    let mut start = 0.0;
+    let mut stops = vec![];
+    for (end, source) in day.source_shares().into_iter().zip(&layout.sources) {
+        if end > start {
+            stops.push(format!(
+                "{} {:.1}% {:.1}%",
+                source.colour,
+                start * 100.0,
+                end * 100.0
+            ));
+            start = end;
+        }
+    }
+
+    Some(format!(
+        "linear-gradient({direction}, {})",
+        stops.join(", ")
+    ))
+}
+
+/// HTML positions boxes by their edges rather than by an anchor and a baseline
+fn render_label(label: &Label, layout: &CalendarLayout) -> String {
+    let left = match label.anchor {
+        Anchor::Left => format!("left: {}px", label.x),
+        Anchor::Right => format!("right: {}px", layout.width.saturating_sub(label.x)),
+    };
+    let top = match label.baseline {
+        Baseline::Alphabetic => label.y.saturating_sub(layout.font_size),
+        Baseline::Central => label.y.saturating_sub(layout.font_size / 2),
+    };
+
+    format!(
+        r#"<span class="user-contrib-text" style="{left}; top: {top}px">{}</span>"#,
+        escape(&label.text)
+    )
+}
+
+/// A box over each covered cell, or a dot in the top right corner of labelled days
+fn render_annotation(annotation: &Annotation, layout: &CalendarLayout) -> Vec<String> {
+    let class = match annotation.kind {
+        AnnotationKind::Streak => "user-contrib-streak",
+        AnnotationKind::Today => "user-contrib-today",
+        AnnotationKind::Holiday => "user-contrib-holiday",
+        AnnotationKind::Label => "user-contrib-label",
+    };
+    annotation
+        .cells
+        .iter()
+        .map(|&(x, y)| {
+            let left = match annotation.kind {
+                AnnotationKind::Label => x + layout.cell_size - layout.cell_size / 2,
+                _ => x,
+            };
+            format!(
+                r#"<div class="user-contrib-annotation {class}" style="left: {left}px; top: {y}px" title="{}"></div>"#,
+                escape(&annotation.text)
+            )
+        })
+        .collect()
+}
+
+fn get_style(layout: &CalendarLayout) -> String {
+    let dark = match &layout.dark_font_colour {
+        None => String::new(),
+        Some(dark_font_colour) => format!(
+            r#"
+
+    @media (prefers-color-scheme: dark) {{
+        .user-contrib-text, .user-contrib-annotation {{
+            color: {dark_font_colour};
+        }}
+
+        .user-contrib-cell, .user-contrib-legend-cell {{
+            background: var(--dark-fill, var(--fill));
+        }}
+    }}"#
+        ),
+    };
+    let shape = match layout.cell_shape {
+        CellShape::Square => String::new(),
+        CellShape::Rounded => format!("\n        border-radius: {}px;", layout.cell_radius),
+        CellShape::Circle => "\n        border-radius: 50%;".into(),
+        CellShape::Hexagon => {
+            "\n        clip-path: polygon(50% 0, 100% 25%, 100% 75%, 50% 100%, 0 75%, 0 25%);"
+                .into()
+        }
+    };
+
+    let has_annotations = layout
+        .calendars
+        .iter()
+        .any(|calendar| !calendar.annotations.is_empty());
+    let annotations = match has_annotations {
+        false => String::new(),
+        true => format!(
+            r#"
+
+    .user-contrib-annotation {{
+        box-sizing: border-box;
+        color: {};
+        pointer-events: none;
+    }}
+
+    .user-contrib-streak, .user-contrib-today, .user-contrib-holiday {{
+        width: {cell_size}px;
+        height: {cell_size}px;{shape}
+    }}
+
+    .user-contrib-streak {{
+        border: 1.5px dashed;
+    }}
+
+    .user-contrib-today {{
+        border: 2px solid;
+    }}
+
+    .user-contrib-holiday {{
+        background: repeating-linear-gradient(45deg, currentColor 0 1.5px, transparent 1.5px 4px);
+        opacity: 0.6;
+    }}
+
+    .user-contrib-label {{
+        width: {dot}px;
+        height: {dot}px;
+        border-radius: 50%;
+        background: currentColor;
+    }}"#,
+            layout.font_colour,
+            cell_size = layout.cell_size,
+            dot = layout.cell_size / 2,
+        ),
+    };
+
+    format!(
+        r#"<style>
+    .contrib-calendar {{
+        position: relative;
+        width: {}px;
+        height: {}px;
+    }}
+
+    .contrib-calendar > * {{
+        position: absolute;
+    }}
+
+    .user-contrib-text {{
+        font-size: {font_size}px;
+        line-height: {font_size}px;
+        font-family: "Noto Sans", Ubuntu, Cantarell, "Helvetica Neue", sans-serif;
+        color: {};
+        white-space: nowrap;
+    }}
+
+    .user-contrib-cell, .user-contrib-legend-cell {{
+        width: {cell_size}px;
+        height: {cell_size}px;{shape}
+        background: var(--fill);
+    }}{annotations}{dark}
+</style>"#,
+        layout.width,
+        layout.height,
+        layout.font_colour,
+        font_size = layout.font_size,
+        cell_size = layout.cell_size,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlRenderer;
+    use crate::{
+        svg::{renderer::Renderer, svg_renderer::Builder},
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn html() {
+        let date = |day| NaiveDate::from_ymd_opt(2023, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(1), 2)]));
+        let layout = Builder {
+            weekday_labels: Some(true),
+            legend: Some(true),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &DateRange::new(date(1), date(7)).unwrap());
+
+        let html = HtmlRenderer.render_layout(&layout);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches(r#"class="user-contrib-cell""#).count(), 7);
+        assert_eq!(
+            html.matches(r#"class="user-contrib-legend-cell""#).count(),
+            5
+        );
+        assert!(html.contains(r#"title="2 contributions on 2023-03-01" data-date="2023-03-01""#));
+        assert!(html.contains(">Mar</span>"));
+        assert!(html.contains("--dark-fill: #"));
+        assert!(html.contains(&format!("width: {}px;", layout.width)));
+        assert!(html.contains("border-radius: 2px;"));
+        assert!(!html.contains(r#"class="user-contrib-annotation"#));
+    }
+
+    #[test]
+    fn annotations() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(4), 1), (date(5), 2)]));
+        let layout = Builder {
+            longest_streak: Some(true),
+            today: Some(date(14)),
+            holidays: Some(
+                "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240311\nDTEND;VALUE=DATE:20240313\nSUMMARY:Leave\nEND:VEVENT"
+                    .into(),
+            ),
+            date_labels: Some(vec!["2024-03-05:v1.0 <beta>".into()]),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &DateRange::new(date(1), date(31)).unwrap());
+
+        let html = HtmlRenderer.render_layout(&layout);
+        let count = |class: &str| {
+            html.matches(&format!(r#"class="user-contrib-annotation {class}""#))
+                .count()
+        };
+        assert_eq!(count("user-contrib-streak"), 2);
+        assert_eq!(count("user-contrib-today"), 1);
+        assert_eq!(count("user-contrib-holiday"), 2);
+        assert_eq!(count("user-contrib-label"), 1);
+        assert!(html.contains(r#"title="v1.0 &lt;beta&gt;""#));
+        assert!(html.contains("border: 1.5px dashed;"));
+
+        let today = layout.days().find(|day| day.date == date(14)).unwrap();
+        assert!(html.contains(&format!(
+            r#"class="user-contrib-annotation user-contrib-today" style="left: {}px; top: {}px""#,
+            today.x, today.y
+        )));
+    }
+}
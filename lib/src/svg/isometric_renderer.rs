@@ -0,0 +1,331 @@
+use super::layout::{Calendar, CalendarLayout, Day, Week};
+use super::renderer::{escape, Renderer};
+use super::rgba::Rgba;
+
+/// Half the width and half the height of a tile relative to the cell size, i.e. cos 30° and sin 30°
+const TILE_WIDTH: f64 = 0.866;
+const TILE_HEIGHT: f64 = 0.5;
+/// The fraction of a tile left empty on each side of a column
+const TILE_GAP: f64 = 0.08;
+const PADDING: f64 = 6.0;
+const MAX_HEIGHT_DEFAULT: usize = 60;
+
+/// Draws each day as an isometric column whose height is proportional to its count.
+/// The top faces have the colours of the layout and the sides are shaded darker.
+/// Weekday labels and the legend are left out, as they cannot be aligned with the columns.
+#[derive(Debug, Clone, Copy)]
+pub struct IsometricRenderer {
+    /// Height in pixels of the column of the busiest day
+    pub max_height: usize,
+}
+
+impl Default for IsometricRenderer {
+    fn default() -> Self {
+        Self {
+            max_height: MAX_HEIGHT_DEFAULT,
+        }
+    }
+}
+
+/// Maps grid coordinates of a calendar to pixels, with weeks running to the bottom right
+/// and weekdays to the bottom left
+struct Projection {
+    x: f64,
+    y: f64,
+    unit_x: f64,
+    unit_y: f64,
+}
+
+impl Projection {
+    /// The pixel position of the corner between `week` and `row`, raised by `z` pixels
+    fn point(&self, week: f64, row: f64, z: f64) -> (f64, f64) {
+        (
+            self.x + (week - row) * self.unit_x,
+            self.y + (week + row) * self.unit_y - z,
+        )
+    }
+
+    fn polygon(&self, corners: [(f64, f64, f64); 4]) -> String {
+        corners
+            .map(|(week, row, z)| {
+                let (x, y) = self.point(week, row, z);
+                format!("{x:.1},{y:.1}")
+            })
+            .join(" ")
+    }
+}
+
+impl Renderer for IsometricRenderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String {
+        let font_size = layout.font_size as f64;
+        let max_count = layout.days().map(|day| day.count).max().unwrap_or(0);
+        let mut content = vec![];
+        let mut width = 0.0_f64;
+        let mut y = PADDING;
+
+        if let Some(caption) = &layout.caption {
+            y += font_size;
+            content.push(render_text(&caption.text, PADDING, y, None));
+            y += PADDING;
+        }
+
+        for calendar in &layout.calendars {
+            if let Some(title) = &calendar.title {
+                y += font_size;
+                content.push(render_text(&title.text, PADDING, y, None));
+                y += PADDING;
+            }
+
+            let (skyline, size) = self.render_calendar(calendar, layout, y, max_count);
+            content.push(skyline);
+            width = width.max(size.0);
+            y += size.1 + PADDING;
+        }
+
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" role="img" aria-label="{}" class="contrib-skyline" data-testid="contrib-skyline">
+    <title>{}</title>
+    <desc>{}</desc>
+    {}
+    {}
+</svg>"#,
+            width.ceil(),
+            y.ceil(),
+            layout.summary.label,
+            layout.summary.title,
+            layout.summary.description,
+            get_style(layout),
+            content.join("\n    ")
+        )
+    }
+}
+
+impl IsometricRenderer {
+    /// Render the columns and month labels of `calendar` with its top edge at `top`,
+    /// returning the SVG and its width and height
+    fn render_calendar(
+        &self,
+        calendar: &Calendar,
+        layout: &CalendarLayout,
+        top: f64,
+        max_count: usize,
+    ) -> (String, (f64, f64)) {
+        let cell_size = layout.cell_size as f64;
+        let font_size = layout.font_size as f64;
+
+        // Columns from left to right, which runs backwards in time for right-to-left layouts
+        let mut weeks: Vec<&Week> = calendar.weeks.iter().collect();
+        weeks.sort_by_key(|week| week.x);
+        let week_count = weeks.len() as f64;
+
+        let projection = Projection {
+            x: PADDING + 7.0 * cell_size * TILE_WIDTH,
+            y: top + self.max_height as f64,
+            unit_x: cell_size * TILE_WIDTH,
+            unit_y: cell_size * TILE_HEIGHT,
+        };
+
+        // Painter's algorithm: columns further back are drawn first
+        let mut days: Vec<(usize, &Day)> = weeks
+            .iter()
+            .enumerate()
+            .flat_map(|(column, week)| week.days.iter().map(move |day| (column, day)))
+            .collect();
+        days.sort_by_key(|(column, day)| (column + day.row, *column));
+
+        let columns = days.into_iter().map(|(column, day)| {
+            let height = match max_count {
+                0 => 0.0,
+                _ => day.count as f64 / max_count as f64 * self.max_height as f64,
+            };
+            let faces = render_column(&projection, column as f64, day, height);
+            format!(
+                r#"<g class="skyline-column" data-date="{}" data-count="{}"><title>{}</title>{faces}</g>"#,
+                day.date,
+                day.count,
+                escape(&day.tooltip)
+            )
+        });
+
+        // The month labels run along the front left edge
+        let month_labels = calendar.month_labels.iter().filter_map(|label| {
+            let column = weeks
+                .iter()
+                .position(|week| week.x <= label.x && label.x <= week.x + layout.cell_size)?;
+            let (x, y) = projection.point(column as f64, 7.0, 0.0);
+            Some(render_text(&label.text, x, y + font_size, Some((x, y))))
+        });
+
+        let content = columns.chain(month_labels).collect::<Vec<_>>().join("");
+        let width = PADDING * 2.0 + (week_count + 7.0) * projection.unit_x;
+        let height =
+            self.max_height as f64 + (week_count + 7.0) * projection.unit_y + font_size * 2.0;
+        (
+            format!(r#"<g data-testid="contrib-skyline-calendar">{content}</g>"#),
+            (width, height),
+        )
+    }
+}
+
+/// The visible top, left and right faces of the column of `day`
+fn render_column(projection: &Projection, week: f64, day: &Day, height: f64) -> String {
+    let (start, end) = (TILE_GAP, 1.0 - TILE_GAP);
+    let (week_start, week_end) = (week + start, week + end);
+    let row = day.row as f64;
+    let (row_start, row_end) = (row + start, row + end);
+
+    let top = projection.polygon([
+        (week_start, row_start, height),
+        (week_end, row_start, height),
+        (week_end, row_end, height),
+        (week_start, row_end, height),
+    ]);
+    let mut faces = vec![(top, 0.0)];
+
+    if height > 0.0 {
+        let left = projection.polygon([
+            (week_start, row_end, height),
+            (week_end, row_end, height),
+            (week_end, row_end, 0.0),
+            (week_start, row_end, 0.0),
+        ]);
+        let right = projection.polygon([
+            (week_end, row_start, height),
+            (week_end, row_end, height),
+            (week_end, row_end, 0.0),
+            (week_end, row_start, 0.0),
+        ]);
+        faces.extend([(left, 0.15), (right, 0.3)]);
+    }
+
+    faces
+        .into_iter()
+        .map(|(points, shade)| {
+            let dark_fill = match &day.dark_colour {
+                None => String::new(),
+                Some(colour) => format!(r#" style="--dark-fill: {}""#, darken(colour, shade)),
+            };
+            format!(
+                r#"<polygon points="{points}" fill="{}"{dark_fill} class="skyline-face"></polygon>"#,
+                darken(&day.colour, shade)
+            )
+        })
+        .collect()
+}
+
+/// Mix `colour` with black, keeping its opacity
+fn darken(colour: &Rgba, factor: f32) -> Rgba {
+    let [_, _, _, alpha] = colour.channels();
+    colour.interpolate(Rgba::new(0, 0, 0, alpha), factor)
+}
+
+/// Text with its start at `(x, y)`, optionally rotated to run parallel to the front left edge
+/// around the point `rotation`
+fn render_text(text: &str, x: f64, y: f64, rotation: Option<(f64, f64)>) -> String {
+    let transform = match rotation {
+        None => String::new(),
+        Some((rx, ry)) => format!(r#" transform="rotate(30 {rx:.1} {ry:.1})""#),
+    };
+    format!(
+        r#"<text x="{x:.1}" y="{y:.1}"{transform} class="user-contrib-text">{}</text>"#,
+        escape(text)
+    )
+}
+
+fn get_style(layout: &CalendarLayout) -> String {
+    let dark = match &layout.dark_font_colour {
+        None => String::new(),
+        Some(dark_font_colour) => format!(
+            r#"
+
+            @media (prefers-color-scheme: dark) {{
+                .user-contrib-text {{
+                    fill: {dark_font_colour};
+                }}
+
+                .skyline-face {{
+                    fill: var(--dark-fill);
+                }}
+            }}"#
+        ),
+    };
+
+    format!(
+        r#"<style>
+            .user-contrib-text {{
+                font-size: {}px;
+                font-family: "Noto Sans", Ubuntu, Cantarell, "Helvetica Neue", sans-serif;
+                fill: {};
+            }}{dark}
+        </style>"#,
+        layout.font_size, layout.font_colour,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsometricRenderer;
+    use crate::{
+        svg::{renderer::Renderer, svg_renderer::Builder},
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn skyline() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let activity =
+            ContributionActivity::from(BTreeMap::from([(date(1, 3), 2), (date(2, 14), 8)]));
+        let layout = Builder {
+            caption: Some(true),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &DateRange::new(date(1, 1), date(3, 31)).unwrap());
+
+        let svg = IsometricRenderer { max_height: 40 }.render_layout(&layout);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches(r#"class="skyline-column""#).count(), 91);
+        // A top face for every day and two sides for each active day
+        assert_eq!(svg.matches("<polygon").count(), 91 + 2 * 2);
+        assert!(svg.contains(
+            r#"data-date="2024-02-14" data-count="8"><title>8 contributions on 2024-02-14</title>"#
+        ));
+        assert!(svg.contains(">Feb</text>"));
+        assert!(svg.contains(">10 contributions from 2024-01-01 to 2024-03-31</text>"));
+        assert!(svg.contains("fill: var(--dark-fill);"));
+    }
+
+    #[test]
+    fn column_heights() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(1), 1), (date(2), 4)]));
+        let layout = Builder::default()
+            .build()
+            .unwrap()
+            .layout(&activity, &DateRange::new(date(1), date(2)).unwrap());
+        let svg = IsometricRenderer { max_height: 40 }.render_layout(&layout);
+
+        // The side faces span the height of the column between the top and the ground
+        let heights: Vec<f64> = svg
+            .split(r#"class="skyline-column""#)
+            .skip(1)
+            .map(|column| {
+                let ys: Vec<f64> = column
+                    .split(r#"points=""#)
+                    .skip(1)
+                    .flat_map(|points| points.split('"').next().unwrap().split(' '))
+                    .map(|point| point.split(',').nth(1).unwrap().parse().unwrap())
+                    .collect();
+                let top = ys.iter().cloned().fold(f64::MAX, f64::min);
+                let bottom = ys.iter().cloned().fold(f64::MIN, f64::max);
+                bottom - top
+            })
+            .collect();
+        assert_eq!(heights.len(), 2);
+        assert!((heights[1] - heights[0] - 30.0).abs() < 0.2);
+    }
+}
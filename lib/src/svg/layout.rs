@@ -0,0 +1,325 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use chrono::{NaiveDate, Weekday};
+use serde::Serialize;
+
+use super::rgba::Rgba;
+
+/// The geometry and colours of a calendar, independent of the output format.
+/// All coordinates are absolute pixel positions with `y` growing downwards.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalendarLayout {
+    pub width: usize,
+    pub height: usize,
+    pub font_size: usize,
+    pub cell_size: usize,
+    pub cell_shape: CellShape,
+    /// Corner radius of `CellShape::Rounded` cells
+    pub cell_radius: usize,
+    pub font_colour: Rgba,
+    /// Font colour when the viewer prefers a dark colour scheme, if a dark palette is configured
+    pub dark_font_colour: Option<Rgba>,
+    pub first_day_of_week: Weekday,
+    /// The language of the labels, e.g. `de`
+    pub language: String,
+    /// The writing direction of the labels, in which time runs
+    pub direction: Direction,
+    pub summary: Summary,
+    pub caption: Option<Label>,
+    /// A single calendar, or one calendar per year stacked vertically
+    pub calendars: Vec<Calendar>,
+    pub legend: Option<Legend>,
+    /// The sources the cells are split between, empty unless the activity is split by source
+    pub sources: Vec<Source>,
+    pub source_split: SourceSplit,
+    /// Explains the colours of the sources instead of `legend`
+    pub source_legend: Option<SourceLegend>,
+}
+
+/// Textual summary of the activity for assistive technologies and standalone viewers
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub title: String,
+    /// Short label, e.g. "Contribution activity: 12 contributions in the last year"
+    pub label: String,
+    /// Longer description including the number of active days, the busiest day and the longest streak
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Calendar {
+    /// Top edge of the calendar including its title
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Shown above the calendar, such as the year and its contribution count
+    pub title: Option<Label>,
+    /// Left edge of the cell grid, leaving room for the weekday labels
+    pub grid_x: usize,
+    pub weekday_labels: Vec<Label>,
+    pub month_labels: Vec<Label>,
+    pub weeks: Vec<Week>,
+    /// Drawn over the cells in order, without changing their colours
+    pub annotations: Vec<Annotation>,
+}
+
+/// A column of cells, possibly shorter than seven days at the start and end of a range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Week {
+    pub x: usize,
+    pub y: usize,
+    pub days: Vec<Day>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub count: usize,
+    /// The count in words, e.g. "2 contributions"
+    pub description: String,
+    /// e.g. "2 contributions on 2024-03-01"
+    pub tooltip: String,
+    pub x: usize,
+    pub y: usize,
+    /// Days since the first day of the week
+    pub row: usize,
+    pub colour: Rgba,
+    pub dark_colour: Option<Rgba>,
+    /// The count of each of `CalendarLayout::sources`, empty unless the activity is split by source
+    pub source_counts: Vec<usize>,
+}
+
+impl Day {
+    /// The share of each source as fractions of the count, ending at 1
+    pub fn source_shares(&self) -> Vec<f64> {
+        let mut sum = 0;
+        self.source_counts
+            .iter()
+            .map(|count| {
+                sum += count;
+                sum as f64 / self.count.max(1) as f64
+            })
+            .collect()
+    }
+}
+
+/// Context such as a holiday or the longest streak covering some cells
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    /// e.g. the name of a holiday
+    pub text: String,
+    /// Top left corners of the covered cells
+    pub cells: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationKind {
+    /// Outlines the cells
+    Streak,
+    /// Outlines the cell more prominently
+    Today,
+    /// Shades the cells
+    Holiday,
+    /// Marks the corner of the cell
+    Label,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Label {
+    pub text: String,
+    pub x: usize,
+    pub y: usize,
+    pub anchor: Anchor,
+    pub baseline: Baseline,
+}
+
+/// The shape drawn within the square of each cell
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellShape {
+    Square,
+    #[default]
+    Rounded,
+    Circle,
+    /// A hexagon with pointy top and bottom
+    Hexagon,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownCellShapeError(pub String);
+
+impl FromStr for CellShape {
+    type Err = UnknownCellShapeError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "square" => Ok(CellShape::Square),
+            "rounded" => Ok(CellShape::Rounded),
+            "circle" => Ok(CellShape::Circle),
+            "hexagon" => Ok(CellShape::Hexagon),
+            _ => Err(UnknownCellShapeError(name.into())),
+        }
+    }
+}
+
+/// A platform or repository some of the contributions come from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Source {
+    pub name: String,
+    pub colour: Rgba,
+}
+
+/// How a cell is divided between the sources of its contributions
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceSplit {
+    /// Horizontal segments stacked from top to bottom
+    #[default]
+    Stacked,
+    /// Vertical stripes side by side
+    Stripes,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownSourceSplitError(pub String);
+
+impl FromStr for SourceSplit {
+    type Err = UnknownSourceSplitError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "stacked" => Ok(SourceSplit::Stacked),
+            "stripes" => Ok(SourceSplit::Stripes),
+            _ => Err(UnknownSourceSplitError(name.into())),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Ltr => write!(f, "ltr"),
+            Direction::Rtl => write!(f, "rtl"),
+        }
+    }
+}
+
+/// Which end of a label is placed at its `x` coordinate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Anchor {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Which part of a label is placed at its `y` coordinate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Baseline {
+    #[default]
+    Alphabetic,
+    Central,
+}
+
+/// "Less ■■■■■ More" explaining the cell colours
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Legend {
+    /// Top edge of the legend
+    pub y: usize,
+    pub less: Label,
+    pub cells: Vec<LegendCell>,
+    pub more: Label,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LegendCell {
+    pub x: usize,
+    pub y: usize,
+    pub colour: Rgba,
+    pub dark_colour: Option<Rgba>,
+}
+
+/// A cell in the colour of each source followed by its name
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceLegend {
+    /// Top edge of the legend
+    pub y: usize,
+    pub entries: Vec<(LegendCell, Label)>,
+}
+
+impl CalendarLayout {
+    /// All days of all calendars
+    pub fn days(&self) -> impl Iterator<Item = &Day> {
+        self.calendars
+            .iter()
+            .flat_map(|calendar| &calendar.weeks)
+            .flat_map(|week| &week.days)
+    }
+
+    /// Mirror the layout horizontally and reverse the writing direction
+    pub fn mirror(&mut self) {
+        let width = self.width;
+        let cell_size = self.cell_size;
+        let mirror_label = |label: &mut Label| {
+            label.x = width - label.x;
+            label.anchor = match label.anchor {
+                Anchor::Left => Anchor::Right,
+                Anchor::Right => Anchor::Left,
+            };
+        };
+        let mirror_cell = |x: &mut usize| *x = width - *x - cell_size;
+
+        self.direction = match self.direction {
+            Direction::Ltr => Direction::Rtl,
+            Direction::Rtl => Direction::Ltr,
+        };
+        self.caption.iter_mut().for_each(mirror_label);
+
+        for calendar in &mut self.calendars {
+            calendar.grid_x = width - calendar.width;
+            calendar.title.iter_mut().for_each(mirror_label);
+            calendar.weekday_labels.iter_mut().for_each(mirror_label);
+            calendar.month_labels.iter_mut().for_each(mirror_label);
+            for week in &mut calendar.weeks {
+                mirror_cell(&mut week.x);
+                week.days.iter_mut().for_each(|day| mirror_cell(&mut day.x));
+            }
+            for annotation in &mut calendar.annotations {
+                annotation
+                    .cells
+                    .iter_mut()
+                    .for_each(|(x, _)| mirror_cell(x));
+            }
+        }
+
+        if let Some(legend) = &mut self.legend {
+            mirror_label(&mut legend.less);
+            mirror_label(&mut legend.more);
+            legend
+                .cells
+                .iter_mut()
+                .for_each(|cell| mirror_cell(&mut cell.x));
+        }
+
+        if let Some(legend) = &mut self.source_legend {
+            for (cell, label) in &mut legend.entries {
+                mirror_cell(&mut cell.x);
+                mirror_label(label);
+            }
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use chrono::{Datelike, Weekday};
+
+use crate::types::DateRange;
+
+/// Translated labels and number formatting of one language.
+/// Placeholders such as `{count}` are replaced when the texts are used.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// The primary language subtag, e.g. `de`
+    code: &'static str,
+    /// Mirrors the calendar, so that time runs from right to left
+    right_to_left: bool,
+    thousands_separator: &'static str,
+    months: [&'static str; 12],
+    /// Starting with Monday
+    weekdays: [&'static str; 7],
+    title: &'static str,
+    less: &'static str,
+    more: &'static str,
+    // The counts are written as within a sentence and capitalised where needed
+    no_contributions: &'static str,
+    one_contribution: &'static str,
+    contributions: &'static str,
+    count_on_date: &'static str,
+    last_year: &'static str,
+    in_year: &'static str,
+    from_to: &'static str,
+    one_active_day: &'static str,
+    active_days: &'static str,
+    busiest_day: &'static str,
+    longest_streak: &'static str,
+}
+
+pub const LOCALES: [Locale; 6] = [
+    Locale {
+        code: "en",
+        right_to_left: false,
+        thousands_separator: ",",
+        months: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        title: "Contribution activity",
+        less: "Less",
+        more: "More",
+        no_contributions: "no contributions",
+        one_contribution: "1 contribution",
+        contributions: "{count} contributions",
+        count_on_date: "{count} on {date}",
+        last_year: "in the last year",
+        in_year: "in {year}",
+        from_to: "from {from} to {to}",
+        one_active_day: "on 1 active day.",
+        active_days: "on {days} active days.",
+        busiest_day: "The busiest day was {date} with {count}.",
+        longest_streak: "The longest streak lasted {days} days.",
+    },
+    Locale {
+        code: "de",
+        right_to_left: false,
+        thousands_separator: ".",
+        months: [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        title: "Beitragsaktivität",
+        less: "Weniger",
+        more: "Mehr",
+        no_contributions: "keine Beiträge",
+        one_contribution: "1 Beitrag",
+        contributions: "{count} Beiträge",
+        count_on_date: "{count} am {date}",
+        last_year: "im letzten Jahr",
+        in_year: "im Jahr {year}",
+        from_to: "vom {from} bis {to}",
+        one_active_day: "an 1 aktiven Tag.",
+        active_days: "an {days} aktiven Tagen.",
+        busiest_day: "Der aktivste Tag war der {date} mit {count}.",
+        longest_streak: "Die längste Serie dauerte {days} Tage.",
+    },
+    Locale {
+        code: "fr",
+        right_to_left: false,
+        thousands_separator: "\u{202f}",
+        months: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        weekdays: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        title: "Activité de contribution",
+        less: "Moins",
+        more: "Plus",
+        no_contributions: "aucune contribution",
+        one_contribution: "1 contribution",
+        contributions: "{count} contributions",
+        count_on_date: "{count} le {date}",
+        last_year: "au cours de la dernière année",
+        in_year: "en {year}",
+        from_to: "du {from} au {to}",
+        one_active_day: "sur 1 jour actif.",
+        active_days: "sur {days} jours actifs.",
+        busiest_day: "Le jour le plus actif était le {date} avec {count}.",
+        longest_streak: "La plus longue série a duré {days} jours.",
+    },
+    Locale {
+        code: "es",
+        right_to_left: false,
+        thousands_separator: ".",
+        months: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        title: "Actividad de contribuciones",
+        less: "Menos",
+        more: "Más",
+        no_contributions: "ninguna contribución",
+        one_contribution: "1 contribución",
+        contributions: "{count} contribuciones",
+        count_on_date: "{count} el {date}",
+        last_year: "en el último año",
+        in_year: "en {year}",
+        from_to: "del {from} al {to}",
+        one_active_day: "en 1 día activo.",
+        active_days: "en {days} días activos.",
+        busiest_day: "El día más activo fue el {date} con {count}.",
+        longest_streak: "La racha más larga duró {days} días.",
+    },
+    Locale {
+        code: "ar",
+        right_to_left: true,
+        thousands_separator: "٬",
+        months: [
+            "يناير",
+            "فبراير",
+            "مارس",
+            "أبريل",
+            "مايو",
+            "يونيو",
+            "يوليو",
+            "أغسطس",
+            "سبتمبر",
+            "أكتوبر",
+            "نوفمبر",
+            "ديسمبر",
+        ],
+        weekdays: ["ن", "ث", "ر", "خ", "ج", "س", "ح"],
+        title: "نشاط المساهمات",
+        less: "أقل",
+        more: "أكثر",
+        no_contributions: "لا توجد مساهمات",
+        one_contribution: "مساهمة واحدة",
+        contributions: "{count} مساهمات",
+        count_on_date: "{count} في {date}",
+        last_year: "في العام الماضي",
+        in_year: "في {year}",
+        from_to: "من {from} إلى {to}",
+        one_active_day: "في يوم نشط واحد.",
+        active_days: "في {days} أيام نشطة.",
+        busiest_day: "كان أكثر الأيام نشاطًا {date} بـ {count}.",
+        longest_streak: "استمرت أطول سلسلة {days} أيام.",
+    },
+    Locale {
+        code: "he",
+        right_to_left: true,
+        thousands_separator: ",",
+        months: [
+            "ינו׳", "פבר׳", "מרץ", "אפר׳", "מאי", "יוני", "יולי", "אוג׳", "ספט׳", "אוק׳", "נוב׳",
+            "דצמ׳",
+        ],
+        weekdays: ["ב׳", "ג׳", "ד׳", "ה׳", "ו׳", "ש׳", "א׳"],
+        title: "פעילות תרומות",
+        less: "פחות",
+        more: "יותר",
+        no_contributions: "אין תרומות",
+        one_contribution: "תרומה אחת",
+        contributions: "{count} תרומות",
+        count_on_date: "{count} ב־{date}",
+        last_year: "בשנה האחרונה",
+        in_year: "ב־{year}",
+        from_to: "מ־{from} עד {to}",
+        one_active_day: "ביום פעיל אחד.",
+        active_days: "ב־{days} ימים פעילים.",
+        busiest_day: "היום העמוס ביותר היה {date} עם {count}.",
+        longest_streak: "הרצף הארוך ביותר נמשך {days} ימים.",
+    },
+];
+
+pub const ENGLISH: &Locale = &LOCALES[0];
+
+/// Replace the `{name}` placeholders of `template`
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Upper-case the first letter, e.g. for counts at the start of a sentence
+pub(crate) fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+    }
+}
+
+impl Locale {
+    /// Find the locale of a language tag such as `de`, `en-GB` or `ar_EG`
+    pub fn find(tag: &str) -> Option<&'static Locale> {
+        let language = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+        LOCALES.iter().find(|locale| locale.code == language)
+    }
+
+    /// Pick the preferred supported locale of an `Accept-Language` header,
+    /// such as `fr-CH, fr;q=0.9, en;q=0.8`
+    pub fn negotiate(accept_language: &str) -> Option<&'static Locale> {
+        let mut languages: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|language| {
+                let mut parts = language.split(';').map(str::trim);
+                let tag = parts.next()?;
+                let quality = parts
+                    .find_map(|part| part.strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.parse().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        languages.into_iter().find_map(|(tag, _)| Locale::find(tag))
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn right_to_left(&self) -> bool {
+        self.right_to_left
+    }
+
+    pub(crate) fn month(&self, month0: u32) -> &'static str {
+        self.months[month0 as usize]
+    }
+
+    pub(crate) fn weekday(&self, weekday: Weekday) -> &'static str {
+        self.weekdays[weekday.num_days_from_monday() as usize]
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        self.title
+    }
+
+    pub(crate) fn less(&self) -> &'static str {
+        self.less
+    }
+
+    pub(crate) fn more(&self) -> &'static str {
+        self.more
+    }
+
+    /// Format a number with thousands separators, e.g. `1,234`
+    pub(crate) fn format_number(&self, number: usize) -> String {
+        let digits = number.to_string();
+        let mut result = String::new();
+
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                result += self.thousands_separator;
+            }
+            result.push(digit);
+        }
+
+        result
+    }
+
+    /// The count as written within a sentence, e.g. "no contributions" or "2 contributions"
+    pub(crate) fn describe_count(&self, count: usize) -> String {
+        match count {
+            0 => self.no_contributions.to_owned(),
+            1 => self.one_contribution.to_owned(),
+            count => fill(self.contributions, &[("count", &self.format_number(count))]),
+        }
+    }
+
+    /// The tooltip of a day, e.g. "2 contributions on 2024-03-01"
+    pub(crate) fn describe_day(&self, count: usize, date: &str) -> String {
+        fill(
+            self.count_on_date,
+            &[
+                ("count", &capitalise(&self.describe_count(count))),
+                ("date", date),
+            ],
+        )
+    }
+
+    pub(crate) fn describe_range(&self, range: &DateRange) -> String {
+        let year = range.from().year();
+
+        if *range == DateRange::last_year() {
+            self.last_year.to_owned()
+        } else if DateRange::calendar_year(year).as_ref() == Ok(range) {
+            fill(self.in_year, &[("year", &year.to_string())])
+        } else {
+            fill(
+                self.from_to,
+                &[
+                    ("from", &range.from().to_string()),
+                    ("to", &range.to().to_string()),
+                ],
+            )
+        }
+    }
+
+    pub(crate) fn describe_active_days(&self, days: usize) -> String {
+        match days {
+            1 => self.one_active_day.to_owned(),
+            days => fill(self.active_days, &[("days", &self.format_number(days))]),
+        }
+    }
+
+    pub(crate) fn describe_busiest_day(&self, date: &str, count: usize) -> String {
+        fill(
+            self.busiest_day,
+            &[("date", date), ("count", &self.describe_count(count))],
+        )
+    }
+
+    pub(crate) fn describe_longest_streak(&self, days: usize) -> String {
+        fill(self.longest_streak, &[("days", &self.format_number(days))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capitalise, Locale, ENGLISH};
+    use chrono::Weekday;
+
+    #[test]
+    fn format_number() {
+        assert_eq!(ENGLISH.format_number(0), "0");
+        assert_eq!(ENGLISH.format_number(999), "999");
+        assert_eq!(ENGLISH.format_number(1000), "1,000");
+        assert_eq!(ENGLISH.format_number(1234567), "1,234,567");
+
+        let german = Locale::find("de").unwrap();
+        assert_eq!(german.format_number(1234567), "1.234.567");
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(Locale::find("de-AT").unwrap().code(), "de");
+        assert_eq!(Locale::find("AR_eg").unwrap().code(), "ar");
+        assert!(Locale::find("he").unwrap().right_to_left());
+        assert_eq!(Locale::find("tlh"), None);
+    }
+
+    #[test]
+    fn negotiate() {
+        let code = |header| Locale::negotiate(header).map(Locale::code);
+        assert_eq!(code("fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5"), Some("fr"));
+        assert_eq!(code("tlh, en;q=0.1, es;q=0.7"), Some("es"));
+        assert_eq!(code("de;q=0, en-GB"), Some("en"));
+        assert_eq!(code("tlh"), None);
+        assert_eq!(code(""), None);
+    }
+
+    #[test]
+    fn translations() {
+        let german = Locale::find("de").unwrap();
+        assert_eq!(german.month(2), "Mär");
+        assert_eq!(german.weekday(Weekday::Sun), "So");
+        assert_eq!(capitalise(&german.describe_count(0)), "Keine Beiträge");
+        assert_eq!(german.describe_count(1234), "1.234 Beiträge");
+        assert_eq!(
+            german.describe_day(1, "2024-03-01"),
+            "1 Beitrag am 2024-03-01"
+        );
+        assert_eq!(
+            ENGLISH.describe_day(0, "2024-03-01"),
+            "No contributions on 2024-03-01"
+        );
+    }
+}
@@ -1,3 +1,16 @@
+pub mod annotation;
+pub mod chart_renderer;
+pub mod colour_space;
 pub mod contribution_colour;
+mod css_colour;
+pub mod html_renderer;
+pub mod isometric_renderer;
+pub mod layout;
+pub mod locale;
+#[cfg(feature = "png")]
+pub mod raster;
+pub mod renderer;
 pub mod rgba;
 pub mod svg_renderer;
+pub mod terminal_renderer;
+pub mod theme;
@@ -0,0 +1,217 @@
+use std::sync::{Arc, OnceLock};
+
+use resvg::{tiny_skia, usvg};
+
+use crate::types::{Error, Result};
+
+/// The raster image formats a rendered calendar can be encoded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RasterFormat {
+    Png,
+    /// Lossless WebP
+    #[cfg(feature = "webp")]
+    WebP,
+}
+
+impl RasterFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RasterFormat::Png => "image/png",
+            #[cfg(feature = "webp")]
+            RasterFormat::WebP => "image/webp",
+        }
+    }
+}
+
+/// The largest supported pixel ratio, preventing huge allocations
+pub const MAX_PIXEL_RATIO: f32 = 4.0;
+
+/// The most pixels of a raster image, as large cells, fonts or ranges would otherwise
+/// allocate gigabytes. This is enough for several years at the largest pixel ratio.
+pub const MAX_PIXELS: u64 = 16_000_000;
+
+/// Used for the generic `sans-serif` family, as the default (Arial) is often not installed
+const SANS_SERIF_FAMILIES: [&str; 5] = [
+    "Noto Sans",
+    "DejaVu Sans",
+    "Liberation Sans",
+    "Arial",
+    "Helvetica",
+];
+
+/// The system fonts are loaded once, as this is comparatively slow
+fn fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+
+            let installed = |family: &str| {
+                fonts
+                    .faces()
+                    .any(|face| face.families.iter().any(|(name, _)| name == family))
+            };
+            let sans_serif = SANS_SERIF_FAMILIES
+                .into_iter()
+                .find(|family| installed(family))
+                .map(String::from)
+                .or_else(|| Some(fonts.faces().next()?.families.first()?.0.clone()));
+            if let Some(family) = sans_serif {
+                fonts.set_sans_serif_family(family);
+            }
+
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+fn raster_error(error: impl ToString) -> Error {
+    Error::RasterError(error.to_string())
+}
+
+/// Rasterise an SVG produced by `SvgRenderer`.
+/// The `pixel_ratio` scales the image for high density displays, so `2.0` doubles width and height.
+pub fn rasterise(svg: &str, pixel_ratio: f32, format: RasterFormat) -> Result<Vec<u8>> {
+    if !(pixel_ratio > 0.0 && pixel_ratio <= MAX_PIXEL_RATIO) {
+        return Err(raster_error(format!(
+            "pixel ratio must be greater than 0 and at most {MAX_PIXEL_RATIO}"
+        )));
+    }
+
+    let options = usvg::Options {
+        fontdb: fonts(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options).map_err(raster_error)?;
+
+    let size = tree.size().to_int_size().scale_by(pixel_ratio);
+    if let Some(size) = size.filter(|size| size.width() as u64 * size.height() as u64 > MAX_PIXELS)
+    {
+        return Err(raster_error(format!(
+            "the image of {}×{} pixels exceeds {MAX_PIXELS} pixels",
+            size.width(),
+            size.height()
+        )));
+    }
+    let mut pixmap = size
+        .and_then(|size| tiny_skia::Pixmap::new(size.width(), size.height()))
+        .ok_or_else(|| raster_error("invalid image size"))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(pixel_ratio, pixel_ratio),
+        &mut pixmap.as_mut(),
+    );
+
+    match format {
+        RasterFormat::Png => pixmap.encode_png().map_err(raster_error),
+        #[cfg(feature = "webp")]
+        RasterFormat::WebP => encode_webp(&pixmap),
+    }
+}
+
+#[cfg(feature = "webp")]
+fn encode_webp(pixmap: &tiny_skia::Pixmap) -> Result<Vec<u8>> {
+    // tiny-skia stores premultiplied alpha, while WebP expects straight alpha
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let pixel = pixel.demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        })
+        .collect();
+
+    let mut result = vec![];
+    image_webp::WebPEncoder::new(&mut result)
+        .encode(
+            &data,
+            pixmap.width(),
+            pixmap.height(),
+            image_webp::ColorType::Rgba8,
+        )
+        .map_err(raster_error)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rasterise, RasterFormat};
+    use crate::{
+        svg::svg_renderer::Builder,
+        types::{ContributionActivity, DateRange, Error},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    fn svg() -> String {
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date, 3)]));
+        Builder {
+            legend: Some(true),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .render_range(&activity, &DateRange::new(date, date).unwrap())
+    }
+
+    /// Read width and height from the IHDR chunk
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let number = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        (number(16), number(20))
+    }
+
+    #[test]
+    fn png() {
+        let svg = svg();
+        let width: u32 = svg
+            .split(r#"width=""#)
+            .nth(1)
+            .unwrap()
+            .split('"')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        let png = rasterise(&svg, 1.0, RasterFormat::Png).unwrap();
+        let (png_width, png_height) = png_size(&png);
+        assert_eq!(png_width, width);
+
+        let png = rasterise(&svg, 2.0, RasterFormat::Png).unwrap();
+        assert_eq!(png_size(&png), (png_width * 2, png_height * 2));
+    }
+
+    #[test]
+    fn invalid_pixel_ratio() {
+        for pixel_ratio in [0.0, -1.0, 100.0, f32::NAN] {
+            assert!(matches!(
+                rasterise(&svg(), pixel_ratio, RasterFormat::Png),
+                Err(Error::RasterError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn too_large() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="3000" height="2000"></svg>"#;
+        assert!(rasterise(svg, 1.0, RasterFormat::Png).is_ok());
+        assert_eq!(
+            rasterise(svg, 2.0, RasterFormat::Png),
+            Err(Error::RasterError(
+                "the image of 6000×4000 pixels exceeds 16000000 pixels".into()
+            ))
+        );
+    }
+
+    #[cfg(feature = "webp")]
+    #[test]
+    fn webp() {
+        let webp = rasterise(&svg(), 1.0, RasterFormat::WebP).unwrap();
+        assert_eq!(&webp[..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+    }
+}
@@ -0,0 +1,59 @@
+use super::layout::CalendarLayout;
+
+/// An output format for a calendar layout computed by `SvgRenderer::layout`
+pub trait Renderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String;
+}
+
+/// Serialises the layout itself, e.g. for clients drawing the calendar on their own
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String {
+        serde_json::to_string(layout).expect("the layout only contains serialisable values")
+    }
+}
+
+/// Escape `text` for the content or an attribute value of XML and HTML elements
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonRenderer, Renderer};
+    use crate::{
+        svg::svg_renderer::Builder,
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn json() {
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date, 2)]));
+        let layout = Builder {
+            legend: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &DateRange::new(date, date).unwrap());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&JsonRenderer.render_layout(&layout)).unwrap();
+        let day = &json["calendars"][0]["weeks"][0]["days"][0];
+        assert_eq!(day["date"], "2023-03-01");
+        assert_eq!(day["count"], 2);
+        assert_eq!(day["description"], "2 contributions");
+        assert_eq!(day["colour"], "#d2dcffff");
+        assert_eq!(json["first_day_of_week"], "Mon");
+        assert_eq!(json["legend"]["less"]["anchor"], "right");
+        assert_eq!(json["width"], layout.width);
+    }
+}
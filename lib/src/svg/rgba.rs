@@ -4,7 +4,10 @@ use std::{
     ops::{Add, Mul, Sub},
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::colour_space::ColourSpace;
+use super::css_colour::{colour_function, named_colour};
 
 #[derive(PartialEq, Eq, Debug, Clone, Deserialize)]
 pub struct Rgba(u8, u8, u8, u8);
@@ -14,6 +17,11 @@ impl Rgba {
         Self(r, g, b, a)
     }
 
+    /// The red, green, blue and alpha channels
+    pub const fn channels(&self) -> [u8; 4] {
+        [self.0, self.1, self.2, self.3]
+    }
+
     pub(crate) fn interpolate(&self, other: Rgba, factor: f32) -> Rgba {
         let r = self.0 as f32 + factor * (other.0 as f32 - self.0 as f32);
         let g = self.1 as f32 + factor * (other.1 as f32 - self.1 as f32);
@@ -28,6 +36,27 @@ impl Rgba {
     }
 }
 
+impl Rgba {
+    /// Interpolate the colour channels in `colour_space` and the alpha channel linearly
+    pub(crate) fn interpolate_in(
+        &self,
+        other: &Rgba,
+        factor: f32,
+        colour_space: ColourSpace,
+    ) -> Rgba {
+        if colour_space == ColourSpace::Srgb {
+            return self.interpolate(other.clone(), factor);
+        }
+
+        let channels = |c: &Rgba| [c.0, c.1, c.2].map(|c| c as f64 / 255.0);
+        let [r, g, b] = colour_space
+            .interpolate(channels(self), channels(other), factor as f64)
+            .map(|c| (c * 255.0).round() as u8);
+        let a = self.3 as f32 + factor * (other.3 as f32 - self.3 as f32);
+        Rgba(r, g, b, a.round() as u8)
+    }
+}
+
 impl From<&Rgba> for String {
     fn from(value: &Rgba) -> String {
         format!(
@@ -44,11 +73,30 @@ impl Display for Rgba {
     }
 }
 
+/// Serialised as a hex string such as `#216e39ff`
+impl Serialize for Rgba {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&String::from(self))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum StringToRgbaError {
+    /// A hexadecimal colour must have 3, 4, 6 or 8 digits
     InvalidLength,
     NotAscii,
     InvalidHexValue(ParseIntError),
+    /// Neither a hexadecimal colour nor one of the CSS named colours
+    UnknownColourName(String),
+    /// A colour function other than `rgb`, `rgba`, `hsl`, `hsla` or `hwb`
+    UnknownFunction(String),
+    /// A colour function without a closing parenthesis
+    MissingParenthesis,
+    /// A colour function requires three channels, found this many
+    InvalidArgumentCount(usize),
+    InvalidNumber(String),
+    /// The legacy comma separated syntax is not supported by `hwb`
+    UnexpectedCommas,
 }
 
 impl From<ParseIntError> for StringToRgbaError {
@@ -57,41 +105,58 @@ impl From<ParseIntError> for StringToRgbaError {
     }
 }
 
-/// Try to convert a hexadecimal string to `Rgba`.
-/// The string can optionally be prefixed with the `#` character.
+/// Try to convert a CSS colour to `Rgba`.
+/// Supported are hexadecimal colours with 3, 4, 6 or 8 digits, optionally prefixed with the `#` character,
+/// named colours like `rebeccapurple` and the functions `rgb()`, `rgba()`, `hsl()`, `hsla()` and `hwb()`.
 impl TryFrom<String> for Rgba {
     type Error = StringToRgbaError;
 
-    fn try_from(mut value: String) -> Result<Self, Self::Error> {
-        const DEFAULT_ALPHA_VALUE: &str = "ff";
+    fn try_from(value: String) -> Result<Self, Self::Error> {
         use StringToRgbaError::*;
-        fn convert_to_u8(value: &str) -> Result<u8, ParseIntError> {
-            u8::from_str_radix(value, 16)
-        }
 
-        if value.get(0..1) == Some(&"#") {
-            value = value[1..].into();
+        if !value.is_ascii() {
+            return Err(NotAscii);
         }
 
-        if !value.is_ascii() {
-            Err(NotAscii)
+        let value = value.trim().to_ascii_lowercase();
+        if value.contains('(') {
+            colour_function(&value)
+        } else if let Some(hex) = value.strip_prefix('#') {
+            parse_hex(hex)
+        } else if let Some(colour) = named_colour(&value) {
+            Ok(colour)
+        } else if value.chars().all(|c| c.is_ascii_hexdigit()) {
+            parse_hex(&value)
         } else {
-            let alpha = match value.len() {
-                6 => DEFAULT_ALPHA_VALUE,
-                8 => &value[6..8],
-                _ => return Err(InvalidLength),
-            };
-
-            Ok(Self(
-                convert_to_u8(&value[0..2])?,
-                convert_to_u8(&value[2..4])?,
-                convert_to_u8(&value[4..6])?,
-                convert_to_u8(alpha)?,
-            ))
+            Err(UnknownColourName(value))
         }
     }
 }
 
+fn parse_hex(value: &str) -> Result<Rgba, StringToRgbaError> {
+    const DEFAULT_ALPHA_VALUE: &str = "ff";
+    fn convert_to_u8(value: &str) -> Result<u8, ParseIntError> {
+        u8::from_str_radix(value, 16)
+    }
+
+    let value = match value.len() {
+        3 | 4 => value.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => value.to_owned(),
+        _ => return Err(StringToRgbaError::InvalidLength),
+    };
+    let value = match value.len() {
+        6 => format!("{value}{DEFAULT_ALPHA_VALUE}"),
+        _ => value,
+    };
+
+    Ok(Rgba(
+        convert_to_u8(&value[0..2])?,
+        convert_to_u8(&value[2..4])?,
+        convert_to_u8(&value[4..6])?,
+        convert_to_u8(&value[6..8])?,
+    ))
+}
+
 impl Add for Rgba {
     type Output = Self;
 
@@ -203,6 +268,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_string_short() {
+        assert_eq!(
+            Rgba::try_from("#fff".to_string()),
+            Ok(Rgba(255, 255, 255, 255))
+        );
+        assert_eq!(
+            Rgba::try_from("#1238".to_string()),
+            Ok(Rgba(17, 34, 51, 136))
+        );
+    }
+
+    #[test]
+    fn from_string_css() {
+        assert_eq!(Rgba::try_from("Red".to_string()), Ok(Rgba(255, 0, 0, 255)));
+        assert_eq!(
+            Rgba::try_from(" rgb(10 20 30 / 50%) ".to_string()),
+            Ok(Rgba(10, 20, 30, 128))
+        );
+        assert_eq!(
+            Rgba::try_from("hsl(120deg 100% 25%)".to_string()),
+            Ok(Rgba(0, 128, 0, 255))
+        );
+    }
+
+    #[test]
+    fn from_string_errors() {
+        use super::StringToRgbaError::*;
+        assert_eq!(Rgba::try_from("#12345".to_string()), Err(InvalidLength));
+        assert!(matches!(
+            Rgba::try_from("#gggggg".to_string()),
+            Err(InvalidHexValue(_))
+        ));
+        assert_eq!(Rgba::try_from("röd".to_string()), Err(NotAscii));
+        assert_eq!(
+            Rgba::try_from("reddish".to_string()),
+            Err(UnknownColourName("reddish".into()))
+        );
+    }
+
     #[test]
     fn double_conversion() {
         assert_eq!(
@@ -1,22 +1,102 @@
+use super::annotation::{parse_ics, Annotations, InvalidIcsError};
+use super::chart_renderer::{UnknownChartKindError, UnknownPeriodError};
 use super::contribution_colour::ContributionInfo;
+use super::layout::{
+    Anchor, Annotation, AnnotationKind, Baseline, Calendar, CalendarLayout, CellShape, Day,
+    Direction, Label, Legend, LegendCell, Source, SourceLegend, SourceSplit, Summary,
+    UnknownCellShapeError, UnknownSourceSplitError, Week,
+};
+use super::locale::{capitalise, Locale, ENGLISH};
+use super::renderer::{escape, Renderer};
 use super::rgba::{Rgba, StringToRgbaError};
-use crate::types::ContributionActivity;
-use crate::{svg::contribution_colour::ColourStrategy, types::YEAR};
+use crate::statistics::Statistics;
+use crate::svg::colour_space::{ColourSpace, UnknownColourSpaceError};
+use crate::svg::contribution_colour::{ColourStrategy, Scale, UnknownScaleError};
+use crate::svg::theme::{Theme, UnknownThemeError};
+use crate::types::{ContributionActivity, DateRange};
 use chrono::{Datelike, Days, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
 const FONT_SIZE_DEFAULT: usize = 11;
 const CELL_SIZE_DEFAULT: usize = 14;
 const FONT_COLOUR_DEFAULT: Rgba = Rgba::new(58, 56, 63, 255);
+const DARK_FONT_COLOUR_DEFAULT: Rgba = Rgba::new(236, 236, 239, 255);
+/// Distinguishable colours assigned to the sources in order, repeating after the last one
+const SOURCE_COLOURS_DEFAULT: [Rgba; 6] = [
+    Rgba::new(78, 121, 167, 255),
+    Rgba::new(242, 142, 43, 255),
+    Rgba::new(225, 87, 89, 255),
+    Rgba::new(89, 161, 79, 255),
+    Rgba::new(176, 122, 161, 255),
+    Rgba::new(237, 201, 72, 255),
+];
 const COLOUR_STRATEGY_DEFAULT: ColourStrategy = ColourStrategy::GitlabStrategy;
+const FIRST_DAY_OF_WEEK_DEFAULT: Weekday = Weekday::Mon;
 
 #[derive(Default, Debug)]
 pub struct Builder {
     pub font_size: Option<usize>,
     pub cell_size: Option<usize>,
+    /// Shape of the cells: `square`, `rounded`, `circle` or `hexagon`
+    pub cell_shape: Option<String>,
+    /// Corner radius of `rounded` cells
+    pub cell_radius: Option<usize>,
+    /// Space between neighbouring cells
+    pub cell_gap: Option<usize>,
+    /// Space left and right of the calendar
+    pub margin: Option<usize>,
     pub colour_strategy: Option<String>,
+    /// A named theme such as `github` or `halloween`, used instead of a colour strategy
+    pub theme: Option<String>,
     pub active_colour: Option<String>,
     pub inactive_colour: Option<String>,
+    /// Colours of `DiscreteStrategy` and `QuantileStrategy`, starting with days without contributions
+    pub colours: Option<Vec<String>>,
+    /// Minimum contribution count of every colour after the first one, for `DiscreteStrategy`
+    pub thresholds: Option<Vec<usize>>,
+    /// Colour space used by `InterpolationStrategy`, such as `srgb`, `linear-rgb`, `oklab` or `oklch`
+    pub colour_space: Option<String>,
+    /// How counts are mapped onto the gradient of `InterpolationStrategy`:
+    /// `average`, `linear`, `logarithmic`, `square-root` or `percentile-rank`
+    pub scale: Option<String>,
     pub font_colour: Option<String>,
+    /// The weekday each week starts with, such as `Mon`, `sunday` or `Sat`
+    pub first_day_of_week: Option<String>,
+    /// Show the names of the weekdays left of the calendar
+    pub weekday_labels: Option<bool>,
+    /// Show a legend explaining the cell colours below the calendar
+    pub legend: Option<bool>,
+    /// Show the total contribution count above the calendar
+    pub caption: Option<bool>,
+    /// Language of the labels such as `de` or `ar-EG`, right-to-left languages mirror the calendar
+    pub locale: Option<String>,
+    /// Colours of the sources when the activity is split by source, in the order of the sources
+    pub source_colours: Option<Vec<String>>,
+    /// How cells are split between sources: `stacked` or `stripes`
+    pub source_split: Option<String>,
+    /// Outline the longest streak of each calendar
+    pub longest_streak: Option<bool>,
+    /// The date to mark as today
+    pub today: Option<NaiveDate>,
+    /// Holidays or leave to shade, as the content of an iCalendar (`.ics`) file
+    pub holidays: Option<String>,
+    /// Texts attached to dates, formatted as `YYYY-MM-DD:text`
+    pub date_labels: Option<Vec<String>>,
+    /// `fill` to fade the cells in chronologically or `pulse` to pulse the current streak
+    pub animation: Option<String>,
+    /// Colour strategy used when the viewer prefers a dark colour scheme.
+    /// Setting any of the `dark_*` options enables the dark palette,
+    /// falling back to the light colour strategy for unset cell colours.
+    pub dark_colour_strategy: Option<String>,
+    pub dark_theme: Option<String>,
+    pub dark_active_colour: Option<String>,
+    pub dark_inactive_colour: Option<String>,
+    pub dark_colours: Option<Vec<String>>,
+    pub dark_thresholds: Option<Vec<usize>>,
+    pub dark_colour_space: Option<String>,
+    pub dark_scale: Option<String>,
+    pub dark_font_colour: Option<String>,
 }
 
 impl Builder {
@@ -29,7 +109,86 @@ impl Builder {
 pub enum BuilderError {
     UnknownColourStrategy,
     InterpolationParametersMissing,
+    /// At least two colours are required for discrete colour strategies
+    PaletteColoursMissing,
+    /// Thresholds must be ascending and given for every colour after the first one
+    InvalidThresholds,
     InvalidRgbaValue(StringToRgbaError),
+    InvalidWeekday(String),
+    UnknownTheme(String),
+    UnknownColourSpace(String),
+    UnknownScale(String),
+    /// A theme was given together with a colour strategy or colours
+    ThemeConflict,
+    UnknownLocale(String),
+    UnknownCellShape(String),
+    /// Charts aggregate by `week` or `month`
+    UnknownPeriod(String),
+    /// Charts are drawn as `bar` or `line`
+    UnknownChartKind(String),
+    /// The moving average of a chart needs a window of at least one period
+    InvalidMovingAverage,
+    UnknownSourceSplit(String),
+    /// Dates are formatted as `YYYY-MM-DD`
+    InvalidDate(String),
+    /// Date labels are formatted as `YYYY-MM-DD:text`
+    InvalidDateLabel(String),
+    InvalidIcs(String),
+    UnknownAnimation(String),
+}
+
+impl From<UnknownAnimationError> for BuilderError {
+    fn from(value: UnknownAnimationError) -> Self {
+        Self::UnknownAnimation(value.0)
+    }
+}
+
+impl From<InvalidIcsError> for BuilderError {
+    fn from(value: InvalidIcsError) -> Self {
+        Self::InvalidIcs(value.0)
+    }
+}
+
+impl From<UnknownSourceSplitError> for BuilderError {
+    fn from(value: UnknownSourceSplitError) -> Self {
+        Self::UnknownSourceSplit(value.0)
+    }
+}
+
+impl From<UnknownPeriodError> for BuilderError {
+    fn from(value: UnknownPeriodError) -> Self {
+        Self::UnknownPeriod(value.0)
+    }
+}
+
+impl From<UnknownChartKindError> for BuilderError {
+    fn from(value: UnknownChartKindError) -> Self {
+        Self::UnknownChartKind(value.0)
+    }
+}
+
+impl From<UnknownCellShapeError> for BuilderError {
+    fn from(value: UnknownCellShapeError) -> Self {
+        Self::UnknownCellShape(value.0)
+    }
+}
+
+impl From<UnknownThemeError> for BuilderError {
+    fn from(value: UnknownThemeError) -> Self {
+        Self::UnknownTheme(value.0)
+    }
+}
+
+impl From<UnknownColourSpaceError> for BuilderError {
+    fn from(value: UnknownColourSpaceError) -> Self {
+        Self::UnknownColourSpace(value.0)
+    }
+}
+
+impl From<UnknownScaleError> for BuilderError {
+    fn from(value: UnknownScaleError) -> Self {
+        Self::UnknownScale(value.0)
+    }
 }
 
 impl From<StringToRgbaError> for BuilderError {
@@ -50,47 +209,293 @@ impl TryFrom<Builder> for SvgRenderer {
             Some(colour) => Rgba::try_from(colour).map_err(BuilderError::InvalidRgbaValue)?,
         };
 
-        let colour_strategy = match value.colour_strategy.as_ref().map(|s| s.as_str()) {
-            None => COLOUR_STRATEGY_DEFAULT,
-            Some("GitlabStrategy") => ColourStrategy::GitlabStrategy,
-            Some("InterpolationStrategy") => match (value.inactive_colour, value.active_colour) {
-                (Some(inactive), Some(active)) => ColourStrategy::InterpolationStrategy {
-                    inactive_colour: inactive.try_into()?,
-                    active_colour: active.try_into()?,
+        let colour_strategy = ColourOptions {
+            theme: value.theme,
+            strategy: value.colour_strategy,
+            inactive_colour: value.inactive_colour,
+            active_colour: value.active_colour,
+            colours: value.colours,
+            thresholds: value.thresholds,
+            colour_space: value.colour_space,
+            scale: value.scale,
+            infer_from_endpoints: false,
+        }
+        .parse()?
+        .unwrap_or(COLOUR_STRATEGY_DEFAULT);
+
+        let dark_options = ColourOptions {
+            theme: value.dark_theme,
+            strategy: value.dark_colour_strategy,
+            inactive_colour: value.dark_inactive_colour,
+            active_colour: value.dark_active_colour,
+            colours: value.dark_colours,
+            thresholds: value.dark_thresholds,
+            colour_space: value.dark_colour_space,
+            scale: value.dark_scale,
+            infer_from_endpoints: true,
+        };
+        let dark = match (dark_options.parse()?, value.dark_font_colour) {
+            (None, None) => None,
+            (dark_strategy, font_colour) => Some(Palette {
+                colour_strategy: dark_strategy.unwrap_or(colour_strategy.clone()),
+                font_colour: match font_colour {
+                    None => DARK_FONT_COLOUR_DEFAULT,
+                    Some(colour) => Rgba::try_from(colour)?,
                 },
-                _ => Err(BuilderError::InterpolationParametersMissing)?,
+            }),
+        };
+
+        let first_day_of_week = match value.first_day_of_week {
+            None => FIRST_DAY_OF_WEEK_DEFAULT,
+            Some(weekday) => weekday
+                .parse()
+                .map_err(|_| BuilderError::InvalidWeekday(weekday))?,
+        };
+
+        let locale = match value.locale {
+            None => ENGLISH,
+            Some(tag) => Locale::find(&tag).ok_or(BuilderError::UnknownLocale(tag))?,
+        };
+
+        let cell_shape = match value.cell_shape {
+            None => CellShape::default(),
+            Some(shape) => shape.parse()?,
+        };
+
+        let source_colours = match value.source_colours {
+            None => SOURCE_COLOURS_DEFAULT.to_vec(),
+            Some(colours) => colours
+                .into_iter()
+                .map(Rgba::try_from)
+                .collect::<Result<_, _>>()?,
+        };
+        if source_colours.is_empty() {
+            return Err(BuilderError::PaletteColoursMissing);
+        }
+        let source_split = match value.source_split {
+            None => SourceSplit::default(),
+            Some(split) => split.parse()?,
+        };
+
+        let parse_date = |date: &str| {
+            date.parse()
+                .map_err(|_| BuilderError::InvalidDate(date.to_owned()))
+        };
+        let annotations = Annotations {
+            longest_streak: value.longest_streak.unwrap_or(false),
+            today: value.today,
+            holidays: match value.holidays {
+                None => vec![],
+                Some(ics) => parse_ics(&ics)?,
             },
-            Some(_) => Err(BuilderError::UnknownColourStrategy)?,
+            labels: value
+                .date_labels
+                .unwrap_or_default()
+                .into_iter()
+                .map(|label| match label.split_once(':') {
+                    None => Err(BuilderError::InvalidDateLabel(label)),
+                    Some((date, text)) => Ok((parse_date(date)?, text.to_owned())),
+                })
+                .collect::<Result<_, _>>()?,
         };
 
-        const DAY_SPACE: usize = 1;
-        let day_size_with_space = cell_size + DAY_SPACE * 2;
+        let animation = value
+            .animation
+            .map(|animation| animation.parse())
+            .transpose()?;
+
+        let day_size_with_space = cell_size + value.cell_gap.unwrap_or(CELL_GAP_DEFAULT);
 
         Ok(Self {
             font_size,
             cell_size,
+            cell_shape,
+            cell_radius: value.cell_radius.unwrap_or(CELL_RADIUS_DEFAULT),
+            margin: value.margin.unwrap_or(MARGIN_DEFAULT),
             colour_strategy,
             day_size_with_space,
             font_colour,
+            first_day_of_week,
+            weekday_labels: value.weekday_labels.unwrap_or(false),
+            legend: value.legend.unwrap_or(false),
+            caption: value.caption.unwrap_or(false),
+            locale,
+            source_colours,
+            source_split,
+            annotations,
+            animation,
+            dark,
         })
     }
 }
 
+/// The options selecting the colour strategy of one colour scheme
+struct ColourOptions {
+    theme: Option<String>,
+    strategy: Option<String>,
+    inactive_colour: Option<String>,
+    active_colour: Option<String>,
+    colours: Option<Vec<String>>,
+    thresholds: Option<Vec<usize>>,
+    colour_space: Option<String>,
+    scale: Option<String>,
+    /// Whether `inactive_colour` and `active_colour` alone select `InterpolationStrategy`.
+    /// Without a strategy the light palette has always ignored them and kept the default.
+    infer_from_endpoints: bool,
+}
+
+impl ColourOptions {
+    /// Parse a theme or colour strategy by name, inferring the strategy from the given colours
+    /// if no name is given. Returns `None` if no option is set at all.
+    fn parse(self) -> Result<Option<ColourStrategy>, BuilderError> {
+        let has_endpoints = self.inactive_colour.is_some() || self.active_colour.is_some();
+        let has_palette = self.colours.is_some()
+            || self.thresholds.is_some()
+            || self.colour_space.is_some()
+            || self.scale.is_some();
+        let has_colours = has_endpoints || has_palette;
+        let infers = has_palette || (has_endpoints && self.infer_from_endpoints);
+
+        if let Some(theme) = self.theme {
+            return match self.strategy.is_some() || has_colours {
+                false => Ok(Some(theme.parse::<Theme>()?.into())),
+                true => Err(BuilderError::ThemeConflict),
+            };
+        }
+
+        let strategy = match (self.strategy.as_deref(), &self.colours, &self.thresholds) {
+            (Some(strategy), _, _) => strategy,
+            (None, _, _) if !infers => return Ok(None),
+            (None, _, Some(_)) => "DiscreteStrategy",
+            (None, Some(_), None) if self.colour_space.is_none() && self.scale.is_none() => {
+                "QuantileStrategy"
+            }
+            (None, _, None) => "InterpolationStrategy",
+        };
+
+        let strategy = match strategy {
+            "GitlabStrategy" => ColourStrategy::GitlabStrategy,
+            "InterpolationStrategy" => {
+                let mut colours = match (self.inactive_colour, self.colours, self.active_colour) {
+                    (Some(inactive), None, Some(active)) => {
+                        vec![inactive.try_into()?, active.try_into()?]
+                    }
+                    (None, colours @ Some(_), None) => parse_palette(colours)?,
+                    _ => Err(BuilderError::InterpolationParametersMissing)?,
+                };
+                let colour_space = match self.colour_space {
+                    None => ColourSpace::default(),
+                    Some(colour_space) => colour_space.parse()?,
+                };
+                let scale = match self.scale {
+                    None => Scale::default(),
+                    Some(scale) => scale.parse()?,
+                };
+                let active_colour = colours.pop().unwrap();
+                ColourStrategy::InterpolationStrategy {
+                    inactive_colour: colours.remove(0),
+                    active_colour,
+                    intermediate_colours: colours,
+                    colour_space,
+                    scale,
+                }
+            }
+            "DiscreteStrategy" => {
+                let colours = parse_palette(self.colours)?;
+                let thresholds = self.thresholds.unwrap_or_default();
+                if thresholds.len() + 1 != colours.len()
+                    || thresholds.first() == Some(&0)
+                    || !thresholds.is_sorted()
+                {
+                    Err(BuilderError::InvalidThresholds)?
+                }
+                ColourStrategy::DiscreteStrategy {
+                    colours,
+                    thresholds,
+                }
+            }
+            "QuantileStrategy" => ColourStrategy::QuantileStrategy {
+                colours: parse_palette(self.colours)?,
+            },
+            _ => Err(BuilderError::UnknownColourStrategy)?,
+        };
+        Ok(Some(strategy))
+    }
+}
+
+fn parse_palette(colours: Option<Vec<String>>) -> Result<Vec<Rgba>, BuilderError> {
+    let colours = colours
+        .unwrap_or_default()
+        .into_iter()
+        .map(Rgba::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    match colours.len() {
+        0 | 1 => Err(BuilderError::PaletteColoursMissing),
+        _ => Ok(colours),
+    }
+}
+
+/// The colours used for one colour scheme
+pub(crate) struct Palette {
+    pub(crate) colour_strategy: ColourStrategy,
+    pub(crate) font_colour: Rgba,
+}
+
+/// Motion added with CSS keyframes. Viewers without CSS animations and viewers
+/// preferring reduced motion see the static calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Animation {
+    /// The cells fade in one after another in chronological order
+    Fill,
+    /// The cells of the current streak pulse
+    Pulse,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownAnimationError(pub String);
+
+impl FromStr for Animation {
+    type Err = UnknownAnimationError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "fill" => Ok(Animation::Fill),
+            "pulse" => Ok(Animation::Pulse),
+            _ => Err(UnknownAnimationError(name.into())),
+        }
+    }
+}
+
 pub struct SvgRenderer {
-    font_size: usize,
+    pub(crate) font_size: usize,
     cell_size: usize,
-    colour_strategy: ColourStrategy,
+    cell_shape: CellShape,
+    cell_radius: usize,
+    /// Horizontal space around the calendar
+    margin: usize,
+    pub(crate) colour_strategy: ColourStrategy,
     day_size_with_space: usize,
-    font_colour: Rgba,
+    pub(crate) font_colour: Rgba,
+    first_day_of_week: Weekday,
+    weekday_labels: bool,
+    legend: bool,
+    caption: bool,
+    pub(crate) locale: &'static Locale,
+    source_colours: Vec<Rgba>,
+    source_split: SourceSplit,
+    annotations: Annotations,
+    animation: Option<Animation>,
+    /// Palette applied via `prefers-color-scheme: dark`, if any
+    pub(crate) dark: Option<Palette>,
 }
 
-const FIRST_DAY_OF_WEEK: Weekday = Weekday::Mon;
+const DAY_SPACE: usize = 1;
 const EXTRA_PADDING: usize = 6;
-const MARGIN_HORIZONTAL: usize = 6;
-
-const MONTH_NAMES: [&str; 12] = [
-    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
-];
+const CELL_GAP_DEFAULT: usize = DAY_SPACE * 2;
+const CELL_RADIUS_DEFAULT: usize = 2;
+const MARGIN_DEFAULT: usize = 6;
+/// Seconds until the last cell starts fading in
+const FILL_DURATION: f64 = 2.0;
 
 #[derive(Debug)]
 struct Data {
@@ -98,44 +503,575 @@ struct Data {
     date: NaiveDate,
 }
 
+/// The counts the colour of each day is relative to
+struct ColourScale {
+    average_count_per_day: f32,
+    /// Contribution counts of all active days, sorted ascendingly
+    active_counts: Vec<usize>,
+}
+
+impl ColourScale {
+    /// The scale of the days with `counts`, including those without contributions
+    fn new(counts: impl Iterator<Item = usize>) -> Self {
+        let mut day_count = 0;
+        let mut active_counts = vec![];
+        for count in counts {
+            day_count += 1;
+            if count > 0 {
+                active_counts.push(count);
+            }
+        }
+        active_counts.sort();
+
+        Self {
+            average_count_per_day: active_counts.iter().sum::<usize>() as f32 / day_count as f32,
+            active_counts,
+        }
+    }
+}
+
+/// The class starting the animation of a cell and its delay in seconds
+struct CellAnimation {
+    class: &'static str,
+    delay: Option<f64>,
+}
+
 struct MonthText {
     group: usize,
     month: u32,
 }
 
-impl MonthText {
-    fn get_text(&self) -> String {
-        MONTH_NAMES[self.month as usize].to_owned()
+/// The attribute carrying the dark-mode colour of a cell, picked up by the style sheet
+fn dark_fill(colour: Option<&Rgba>) -> String {
+    match colour {
+        None => String::new(),
+        Some(colour) => format!(r#" style="--dark-fill: {colour}""#),
     }
+}
 
-    fn render(&self, renderer: &SvgRenderer) -> String {
-        let x = renderer.day_size_with_space * self.group + MARGIN_HORIZONTAL;
-        let y = renderer.font_size;
+pub(crate) fn summarise(
+    activity: &ContributionActivity,
+    range: &DateRange,
+    locale: &Locale,
+) -> Summary {
+    let statistics = Statistics::new(&activity.within(range));
+    let total = capitalise(&format!(
+        "{} {}",
+        locale.describe_count(statistics.contribution_count),
+        locale.describe_range(range)
+    ));
 
-        format!(
-            r#"<text x="{}" y="{}" class="user-contrib-text">{}</text>"#,
-            x,
-            y,
-            self.get_text()
-        )
+    let mut description = format!(
+        "{total} {}",
+        locale.describe_active_days(statistics.active_days)
+    );
+    if let Some(day) = statistics.busiest_day {
+        description += " ";
+        description += &locale.describe_busiest_day(&day.date.to_string(), day.count);
+    }
+    if let Some(streak) = statistics.longest_streak.filter(|streak| streak.days > 1) {
+        description += " ";
+        description += &locale.describe_longest_streak(streak.days);
+    }
+
+    Summary {
+        title: locale.title().to_owned(),
+        label: format!("{}: {total}", locale.title()),
+        description,
     }
 }
 
 impl SvgRenderer {
+    /// Render the activity of the last year
     pub fn render(&self, activity: &ContributionActivity) -> String {
-        let today = chrono::Local::now().date_naive();
-        self.render_at(activity, today)
+        self.render_range(activity, &DateRange::last_year())
+    }
+
+    /// Render exactly the days within `range`
+    pub fn render_range(&self, activity: &ContributionActivity, range: &DateRange) -> String {
+        self.render_layout(&self.layout(activity, range))
+    }
+
+    /// Render one calendar per year within `range`, stacked vertically.
+    /// Each calendar is labelled with its year and the contribution count of that year.
+    pub fn render_years(&self, activity: &ContributionActivity, range: &DateRange) -> String {
+        self.render_layout(&self.layout_years(activity, range))
+    }
+
+    /// Compute the layout of exactly the days within `range`,
+    /// which any `Renderer` can turn into its output format
+    pub fn layout(&self, activity: &ContributionActivity, range: &DateRange) -> CalendarLayout {
+        let calendar =
+            self.layout_calendar(activity, range, self.caption_height(), None, None, true);
+        self.decorate(activity, range, vec![calendar], vec![])
+    }
+
+    /// Compute the layout of one calendar per year within `range`, stacked vertically
+    pub fn layout_years(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+    ) -> CalendarLayout {
+        self.decorate(
+            activity,
+            range,
+            self.layout_calendars(activity, range),
+            vec![],
+        )
+    }
+
+    /// Like `layout`, but splitting each cell between the named `sources` of the contributions
+    pub fn layout_sources(
+        &self,
+        sources: &[(String, ContributionActivity)],
+        range: &DateRange,
+    ) -> CalendarLayout {
+        let activity = total(sources);
+        let calendar =
+            self.layout_calendar(&activity, range, self.caption_height(), None, None, true);
+        self.split(&activity, range, vec![calendar], sources)
+    }
+
+    /// Like `layout_years`, but splitting each cell between the named `sources` of the contributions
+    pub fn layout_years_sources(
+        &self,
+        sources: &[(String, ContributionActivity)],
+        range: &DateRange,
+    ) -> CalendarLayout {
+        let activity = total(sources);
+        let calendars = self.layout_calendars(&activity, range);
+        self.split(&activity, range, calendars, sources)
+    }
+
+    /// Compute the layout of one calendar per named activity, stacked vertically and aligned on
+    /// the same dates. The colours of all rows share one scale and only the first row has month labels.
+    pub fn layout_comparison(
+        &self,
+        rows: &[(String, ContributionActivity)],
+        range: &DateRange,
+    ) -> CalendarLayout {
+        let scale = ColourScale::new(rows.iter().flat_map(|(_, activity)| {
+            range
+                .from()
+                .iter_days()
+                .take_while(|date| *date <= range.to())
+                .map(|date| activity.get(&date).unwrap_or(0))
+        }));
+        let mut y = self.caption_height();
+
+        let calendars = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (name, activity))| {
+                let total = self
+                    .locale
+                    .describe_count(activity.within(range).contribution_count());
+                let title = Label {
+                    text: format!("{name}: {total}"),
+                    x: self.margin,
+                    y: y + self.font_size,
+                    anchor: Anchor::Left,
+                    baseline: Baseline::Alphabetic,
+                };
+
+                let calendar =
+                    self.layout_calendar(activity, range, y, Some(title), Some(&scale), i == 0);
+                y += calendar.height;
+                calendar
+            })
+            .collect();
+
+        self.decorate(&total(rows), range, calendars, vec![])
+    }
+
+    /// Note the count of every source on each day and decorate the calendars with a source legend
+    fn split(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+        mut calendars: Vec<Calendar>,
+        sources: &[(String, ContributionActivity)],
+    ) -> CalendarLayout {
+        let days = calendars
+            .iter_mut()
+            .flat_map(|calendar| &mut calendar.weeks)
+            .flat_map(|week| &mut week.days);
+        for day in days {
+            day.source_counts = sources
+                .iter()
+                .map(|(_, activity)| activity.get(&day.date).unwrap_or(0))
+                .collect();
+        }
+
+        let sources = sources
+            .iter()
+            .zip(self.source_colours.iter().cycle())
+            .map(|((name, _), colour)| Source {
+                name: name.clone(),
+                colour: colour.clone(),
+            })
+            .collect();
+        self.decorate(activity, range, calendars, sources)
+    }
+
+    /// One calendar per year within `range`, stacked vertically below the caption
+    fn layout_calendars(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+    ) -> Vec<Calendar> {
+        let mut y = self.caption_height();
+
+        (range.from().year()..=range.to().year())
+            .filter_map(|year| {
+                Some((
+                    year,
+                    DateRange::calendar_year(year).ok()?.intersection(range)?,
+                ))
+            })
+            .map(|(year, year_range)| {
+                let total = self
+                    .locale
+                    .describe_count(activity.within(&year_range).contribution_count());
+                let title = Label {
+                    text: format!("{year}: {total}"),
+                    x: self.margin,
+                    y: y + self.font_size,
+                    anchor: Anchor::Left,
+                    baseline: Baseline::Alphabetic,
+                };
+
+                let calendar =
+                    self.layout_calendar(activity, &year_range, y, Some(title), None, true);
+                y += calendar.height;
+                calendar
+            })
+            .collect()
+    }
+
+    fn caption_height(&self) -> usize {
+        match self.caption {
+            true => self.font_size + EXTRA_PADDING,
+            false => 0,
+        }
+    }
+
+    /// Add the optional caption and legend around `calendars`,
+    /// explaining the colours of the `sources` instead of the colour strategy if there are any
+    fn decorate(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+        mut calendars: Vec<Calendar>,
+        sources: Vec<Source>,
+    ) -> CalendarLayout {
+        calendars
+            .iter_mut()
+            .for_each(|calendar| self.annotate(calendar));
+
+        let caption = self.caption.then(|| Label {
+            text: capitalise(&format!(
+                "{} {}",
+                self.locale
+                    .describe_count(activity.within(range).contribution_count()),
+                self.locale.describe_range(range)
+            )),
+            x: self.margin,
+            y: self.font_size,
+            anchor: Anchor::Left,
+            baseline: Baseline::Alphabetic,
+        });
+
+        let mut width = calendars
+            .iter()
+            .map(|calendar| calendar.width)
+            .max()
+            .unwrap_or_default();
+        let mut height = self.caption_height()
+            + calendars
+                .iter()
+                .map(|calendar| calendar.height)
+                .sum::<usize>();
+
+        let legend = (self.legend && sources.is_empty()).then(|| {
+            let (legend, legend_width, legend_height) = self.layout_legend(height);
+            width = width.max(legend_width);
+            height += legend_height;
+            legend
+        });
+        let source_legend = (self.legend && !sources.is_empty()).then(|| {
+            let (legend, legend_width, legend_height) = self.layout_source_legend(height, &sources);
+            width = width.max(legend_width);
+            height += legend_height;
+            legend
+        });
+
+        let mut layout = CalendarLayout {
+            width,
+            height,
+            font_size: self.font_size,
+            cell_size: self.cell_size,
+            cell_shape: self.cell_shape,
+            cell_radius: self.cell_radius,
+            font_colour: self.font_colour.clone(),
+            dark_font_colour: self.dark.as_ref().map(|dark| dark.font_colour.clone()),
+            first_day_of_week: self.first_day_of_week,
+            language: self.locale.code().to_owned(),
+            direction: Direction::Ltr,
+            summary: summarise(activity, range, self.locale),
+            caption,
+            calendars,
+            legend,
+            sources,
+            source_split: self.source_split,
+            source_legend,
+        };
+        if self.locale.right_to_left() {
+            layout.mirror();
+        }
+        layout
+    }
+
+    /// Cover the cells of `calendar` with the configured annotations, from the bottom to the top,
+    /// and append the texts of holidays and labels to the tooltips of their days
+    fn annotate(&self, calendar: &mut Calendar) {
+        let annotations = &self.annotations;
+        let mut result = vec![];
+        let mut annotate = |kind, text: &str, days: Vec<&Day>| {
+            if !days.is_empty() {
+                result.push(Annotation {
+                    kind,
+                    text: text.to_owned(),
+                    cells: days.iter().map(|day| (day.x, day.y)).collect(),
+                });
+            }
+        };
+        let days = || calendar.weeks.iter().flat_map(|week| &week.days);
+
+        for holiday in &annotations.holidays {
+            let days = days().filter(|day| holiday.range.contains(&day.date));
+            annotate(AnnotationKind::Holiday, &holiday.name, days.collect());
+        }
+        if annotations.longest_streak {
+            let streak = longest_streak(days());
+            if streak.len() > 1 {
+                let text = self.locale.describe_longest_streak(streak.len());
+                annotate(AnnotationKind::Streak, &text, streak);
+            }
+        }
+        for (date, text) in &annotations.labels {
+            let days = days().filter(|day| day.date == *date);
+            annotate(AnnotationKind::Label, text, days.collect());
+        }
+        if let Some(today) = annotations.today {
+            let days = days().filter(|day| day.date == today);
+            annotate(AnnotationKind::Today, &today.to_string(), days.collect());
+        }
+        calendar.annotations = result;
+
+        let days = calendar.weeks.iter_mut().flat_map(|week| &mut week.days);
+        for day in days {
+            let holidays = annotations
+                .holidays
+                .iter()
+                .filter(|holiday| holiday.range.contains(&day.date))
+                .map(|holiday| &holiday.name);
+            let labels = annotations
+                .labels
+                .iter()
+                .filter(|(date, _)| *date == day.date)
+                .map(|(_, text)| text);
+            for text in holidays.chain(labels) {
+                day.tooltip += " · ";
+                day.tooltip += text;
+            }
+        }
+    }
+
+    /// Lay out "Less ■■■■■ More" with the colours of the colour strategy at `y`,
+    /// returning the legend with its width and height
+    fn layout_legend(&self, y: usize) -> (Legend, usize, usize) {
+        let text_y = y + self.cell_size / 2;
+        let start = self.label_width();
+        let colours = self.colour_strategy.legend();
+        let dark_colours = self.dark.as_ref().map(|dark| dark.colour_strategy.legend());
+        let end = start + colours.len() * self.day_size_with_space;
+
+        let cells = colours
+            .into_iter()
+            .enumerate()
+            .map(|(i, colour)| LegendCell {
+                x: start + i * self.day_size_with_space,
+                y,
+                colour,
+                dark_colour: dark_colours
+                    .as_ref()
+                    .and_then(|colours| colours.get(i).cloned()),
+            })
+            .collect();
+
+        let legend = Legend {
+            y,
+            less: Label {
+                text: self.locale.less().to_owned(),
+                x: start.saturating_sub(DAY_SPACE * 2),
+                y: text_y,
+                anchor: Anchor::Right,
+                baseline: Baseline::Central,
+            },
+            cells,
+            more: Label {
+                text: self.locale.more().to_owned(),
+                x: end,
+                y: text_y,
+                anchor: Anchor::Left,
+                baseline: Baseline::Central,
+            },
+        };
+
+        let width = end + self.label_width();
+        let height = self.cell_size.max(self.font_size) + EXTRA_PADDING;
+        (legend, width, height)
+    }
+
+    /// Lay out a cell in the colour of each source followed by its name at `y`,
+    /// returning the legend with its width and height
+    fn layout_source_legend(&self, y: usize, sources: &[Source]) -> (SourceLegend, usize, usize) {
+        let mut x = self.label_width();
+        let entries = sources
+            .iter()
+            .map(|source| {
+                let cell = LegendCell {
+                    x,
+                    y,
+                    colour: source.colour.clone(),
+                    dark_colour: None,
+                };
+                let label = Label {
+                    text: source.name.clone(),
+                    x: x + self.day_size_with_space,
+                    y: y + self.cell_size / 2,
+                    anchor: Anchor::Left,
+                    baseline: Baseline::Central,
+                };
+                // Estimate the width of the name, as fonts are not measured
+                x = label.x + source.name.chars().count() * self.font_size * 2 / 3 + self.font_size;
+                (cell, label)
+            })
+            .collect();
+
+        let height = self.cell_size.max(self.font_size) + EXTRA_PADDING;
+        (SourceLegend { y, entries }, x + self.margin, height)
+    }
+
+    /// The horizontal space reserved for short labels such as weekday names
+    fn label_width(&self) -> usize {
+        self.font_size * 2 + self.margin
+    }
+
+    /// Lay out the calendar of `range` including the optional title and weekday labels,
+    /// with its top edge at `y`. The colours are relative to the days of the calendar
+    /// unless a common `scale` is given.
+    fn layout_calendar(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+        y: usize,
+        title: Option<Label>,
+        scale: Option<&ColourScale>,
+        month_labels: bool,
+    ) -> Calendar {
+        let title_height = match title {
+            Some(_) => self.font_size + EXTRA_PADDING,
+            None => 0,
+        };
+        let top = y + title_height;
+        let grid_x = match self.weekday_labels {
+            true => self.label_width(),
+            false => 0,
+        };
+        let month_height = match month_labels {
+            true => self.font_size + EXTRA_PADDING,
+            false => 0,
+        };
+        let grid_y = top + month_height;
+
+        let (weeks, months) = self.group_weeks(activity, range);
+        let week_count = weeks.len();
+
+        let own_scale = ColourScale::new(weeks.iter().flatten().map(|day| day.count));
+        let scale = scale.unwrap_or(&own_scale);
+
+        let weeks = weeks
+            .into_iter()
+            .enumerate()
+            .map(|(week, days)| {
+                let x = grid_x + self.day_size_with_space * week + self.margin;
+                let days = days
+                    .into_iter()
+                    .map(|day| self.layout_day(day, x, grid_y, scale))
+                    .collect();
+                Week { x, y: grid_y, days }
+            })
+            .collect();
+
+        let month_labels = months
+            .iter()
+            .filter(|_| month_labels)
+            .map(|month| Label {
+                text: self.locale.month(month.month).to_owned(),
+                x: grid_x + self.day_size_with_space * month.group + self.margin,
+                y: top + self.font_size,
+                anchor: Anchor::Left,
+                baseline: Baseline::Alphabetic,
+            })
+            .collect();
+
+        let weekday_labels = match self.weekday_labels {
+            false => vec![],
+            true => (0..7)
+                .step_by(2)
+                .map(|row| {
+                    let weekday = (0..row).fold(self.first_day_of_week, |day, _| day.succ());
+                    Label {
+                        text: self.locale.weekday(weekday).to_owned(),
+                        x: grid_x,
+                        y: grid_y + self.day_size_with_space * row + self.cell_size / 2,
+                        anchor: Anchor::Right,
+                        baseline: Baseline::Central,
+                    }
+                })
+                .collect(),
+        };
+
+        Calendar {
+            y,
+            width: grid_x + week_count * self.day_size_with_space + self.margin,
+            height: title_height + month_height + 7 * self.day_size_with_space,
+            title,
+            grid_x,
+            weekday_labels,
+            month_labels,
+            weeks,
+            annotations: vec![],
+        }
     }
 
-    fn render_at(&self, activity: &ContributionActivity, last_day: NaiveDate) -> String {
+    /// Split the days of `range` into weeks, noting the week in which each month starts
+    fn group_weeks(
+        &self,
+        activity: &ContributionActivity,
+        range: &DateRange,
+    ) -> (Vec<Vec<Data>>, Vec<MonthText>) {
         let mut result: Vec<Vec<Data>> = vec![]; // todo: functional instead of this weird imperative style
         let mut months: Vec<MonthText> = vec![];
-        let mut day = last_day.clone() - YEAR;
+        let mut day = range.from();
+        let last_day = range.to();
 
         let initial_day = day;
 
         while day <= last_day {
-            if day.weekday() == FIRST_DAY_OF_WEEK || day == initial_day {
+            if day.weekday() == self.first_day_of_week || day == initial_day {
                 let month = day.month0();
                 let is_new_month = match months.last() {
                     None => true,
@@ -152,109 +1088,572 @@ impl SvgRenderer {
                 result.push(vec![]);
             }
 
-            let date = NaiveDate::from_ymd_opt(
-                day.year(),
-                (day.month() as u8).try_into().unwrap(),
-                day.day(),
-            )
-            .unwrap();
-
-            let count = activity.get(&date).unwrap_or(0);
+            let count = activity.get(&day).unwrap_or(0);
             let result_index = result.len() - 1;
-            result[result_index].push(Data { count, date });
+            result[result_index].push(Data { count, date: day });
 
             day = day.checked_add_days(Days::new(1)).unwrap();
         }
 
-        let result_count = result.len();
-        let content = self.render_week_rows(result) + "\n" + &self.render_text(months);
+        (result, months)
+    }
+
+    fn layout_day(&self, day: Data, x: usize, grid_y: usize, scale: &ColourScale) -> Day {
+        let row = day.date.weekday().days_since(self.first_day_of_week) as usize;
+        let info = || ContributionInfo {
+            average_count_per_day: scale.average_count_per_day,
+            count_today: day.count,
+            active_counts: &scale.active_counts,
+        };
 
-        let width = result_count * self.day_size_with_space + MARGIN_HORIZONTAL;
-        let height = self.font_size + 7 * self.day_size_with_space + EXTRA_PADDING;
-        self.wrap_svg(width, height, &content)
+        Day {
+            date: day.date,
+            count: day.count,
+            description: capitalise(&self.locale.describe_count(day.count)),
+            tooltip: self.locale.describe_day(day.count, &day.date.to_string()),
+            x,
+            y: grid_y + self.day_size_with_space * row,
+            row,
+            colour: self.colour_strategy.get_colour(info()),
+            dark_colour: self
+                .dark
+                .as_ref()
+                .map(|dark| dark.colour_strategy.get_colour(info())),
+            source_counts: vec![],
+        }
     }
+}
 
-    fn wrap_svg(&self, width: usize, height: usize, content: &str) -> String {
-        format!(
-            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" class="contrib-calendar" data-testid="contrib-calendar">
-    {}
-    {}
-</svg>"#,
-            width,
-            height,
-            self.get_style(),
-            content
-        )
+/// The longest run of consecutive active days, the earliest one if there are several
+fn longest_streak<'a>(days: impl Iterator<Item = &'a Day>) -> Vec<&'a Day> {
+    let mut longest = vec![];
+    let mut current = vec![];
+    for day in days {
+        match day.count {
+            0 => current.clear(),
+            _ => {
+                current.push(day);
+                if current.len() > longest.len() {
+                    longest = current.clone();
+                }
+            }
+        }
     }
+    longest
+}
 
-    fn render_week_rows(&self, result: Vec<Vec<Data>>) -> String {
-        let day_count = result.iter().map(|week| week.len()).sum::<usize>();
-        let average_count_per_day = result
-            .iter()
-            .map(|week| week.iter().map(|day| day.count).sum::<usize>())
-            .sum::<usize>() as f32
-            / day_count as f32;
+/// The sum of the contributions of all sources
+fn total(sources: &[(String, ContributionActivity)]) -> ContributionActivity {
+    sources
+        .iter()
+        .fold(ContributionActivity::new(), |total, (_, activity)| {
+            total + activity.clone()
+        })
+}
 
-        let content = result
-            .into_iter()
-            .enumerate()
-            .map(|(week, day_elements)| {
-                let x = self.day_size_with_space * week + MARGIN_HORIZONTAL;
-                let y = self.font_size + EXTRA_PADDING;
-                let week_day_cells =
-                    self.render_week_day_cells(day_elements, average_count_per_day);
-                format!(
-                    r#"<g transform="translate({}, {})" data-testid="user-contrib-cell-group">
+impl Renderer for SvgRenderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String {
+        let label_height = layout.font_size + EXTRA_PADDING;
+        let caption_height = match layout.caption {
+            Some(_) => label_height,
+            None => 0,
+        };
+
+        let animations = self.animate(layout);
+        let mut content = layout
+            .calendars
+            .iter()
+            .map(|calendar| match &calendar.title {
+                None => render_calendar(calendar, calendar.y, layout, &animations),
+                Some(title) => format!(
+                    r#"<g transform="translate(0, {})" data-testid="user-contrib-year">
 {}
+<g transform="translate(0, {label_height})">
+{}
+</g>
 </g>"#,
-                    x, y, week_day_cells
-                )
+                    calendar.y - caption_height,
+                    render_text(title, 0, calendar.y),
+                    render_calendar(calendar, calendar.y + label_height, layout, &animations),
+                ),
             })
             .collect::<Vec<_>>()
             .join("\n");
-        content
-    }
 
-    fn render_week_day_cells(&self, days: Vec<Data>, average_count_per_day: f32) -> String {
-        let cell_size: usize = self.cell_size;
-        const CELL_RADIUS: usize = 2;
-        const FIST_DAY_OF_WEEK: usize = 0; // todo
+        let mut annotations = layout
+            .calendars
+            .iter()
+            .flat_map(|calendar| &calendar.annotations);
+        if annotations.any(|annotation| annotation.kind == AnnotationKind::Holiday) {
+            content = format!(
+                r#"<defs><pattern id="user-contrib-hatch" width="4" height="4" patternUnits="userSpaceOnUse" patternTransform="rotate(45)"><line x1="0" y1="0" x2="0" y2="4" stroke="{}" stroke-width="1.5" stroke-opacity="0.6" class="user-contrib-hatch"></line></pattern></defs>
+{content}"#,
+                layout.font_colour
+            );
+        }
+
+        if let Some(caption) = &layout.caption {
+            content = format!(
+                r#"<text x="{}" y="{}" class="user-contrib-text" data-testid="user-contrib-caption">{}</text>
+<g transform="translate(0, {caption_height})">
+{content}
+</g>"#,
+                caption.x,
+                caption.y,
+                escape(&caption.text)
+            );
+        }
 
-        days.into_iter()
-            .map(|day| {
-                let hover_info = format!("{}", match day.count {
-                    0 => "No contributions".to_owned(),
-                    1 => "1 contribution".to_owned(),
-                    i => format!("{} contributions", i),
-                });
+        if let Some(legend) = &layout.legend {
+            content = format!(
+                r#"{content}
+<g transform="translate(0, {})" data-testid="user-contrib-legend">
+{}
+</g>"#,
+                legend.y,
+                render_legend(legend, layout)
+            );
+        }
 
-                let y = self.day_size_with_space * ((day.date.weekday().num_days_from_monday() as usize + 7 - FIST_DAY_OF_WEEK) % 7);
-                let data_date = day.date.to_string();
-                let colour = self.colour_strategy.get_colour(ContributionInfo {
-                    average_count_per_day,
-                    count_today: day.count,
-                });
+        if let Some(legend) = &layout.source_legend {
+            content = format!(
+                r#"{content}
+<g transform="translate(0, {})" data-testid="user-contrib-source-legend">
+{}
+</g>"#,
+                legend.y,
+                render_source_legend(legend, layout)
+            );
+        }
 
-                format!(r#"<rect x="0" y="{y}" rx="{CELL_RADIUS}" ry="{CELL_RADIUS}" width="{cell_size}" height="{cell_size}" fill="{colour}" data-hover-info="{hover_info}" data-date="{data_date}" class="user-contrib-cell has-tooltip"></rect>"#)
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
+        wrap_svg(layout, &content, self.animation)
     }
+}
 
-    fn render_text(&self, months: Vec<MonthText>) -> String {
-        format!(
-            r#"<g direction="ltr">{}</g>"#,
-            months
-                .iter()
-                .map(|month| month.render(&self))
-                .collect::<Vec<_>>()
+impl SvgRenderer {
+    /// The animation of each animated cell by date
+    fn animate(&self, layout: &CalendarLayout) -> BTreeMap<NaiveDate, CellAnimation> {
+        // Whether anybody was active on each date, as comparisons repeat the dates
+        let mut dates = BTreeMap::new();
+        for day in layout.days() {
+            *dates.entry(day.date).or_insert(false) |= day.count > 0;
+        }
+
+        match self.animation {
+            None => BTreeMap::new(),
+            Some(Animation::Fill) => dates
+                .keys()
+                .enumerate()
+                .map(|(i, date)| {
+                    let delay = i as f64 * FILL_DURATION / dates.len() as f64;
+                    (
+                        *date,
+                        CellAnimation {
+                            class: "user-contrib-fill",
+                            delay: Some(delay),
+                        },
+                    )
+                })
+                .collect(),
+            Some(Animation::Pulse) => {
+                let mut days = dates.iter().rev().peekable();
+                // The last day might not be over yet, so the streak may also end the day before
+                days.next_if(|(_, active)| !**active);
+                days.take_while(|(_, active)| **active)
+                    .map(|(date, _)| {
+                        (
+                            *date,
+                            CellAnimation {
+                                class: "user-contrib-pulse",
+                                delay: None,
+                            },
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Render `label` relative to the origin `(x, y)`
+fn render_text(label: &Label, x: usize, y: usize) -> String {
+    let anchor = match label.anchor {
+        Anchor::Left => "",
+        Anchor::Right => r#" text-anchor="end""#,
+    };
+    let baseline = match label.baseline {
+        Baseline::Alphabetic => "",
+        Baseline::Central => r#" dominant-baseline="central""#,
+    };
+
+    format!(
+        r#"<text x="{}" y="{}"{anchor}{baseline} class="user-contrib-text">{}</text>"#,
+        label.x - x,
+        label.y - y,
+        escape(&label.text)
+    )
+}
+
+/// Render the cells, month labels and weekday labels of `calendar` relative to the top edge `top`
+fn render_calendar(
+    calendar: &Calendar,
+    top: usize,
+    layout: &CalendarLayout,
+    animations: &BTreeMap<NaiveDate, CellAnimation>,
+) -> String {
+    let month_labels = calendar
+        .month_labels
+        .iter()
+        .map(|label| render_text(label, calendar.grid_x, top))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let grid = format!(
+        r#"{}{}
+<g direction="ltr">{month_labels}</g>"#,
+        render_weeks(calendar, top, layout, animations),
+        render_annotations(calendar, top, layout)
+    );
+    if calendar.weekday_labels.is_empty() && calendar.grid_x == 0 {
+        return grid;
+    }
+
+    let weekdays = match calendar.weekday_labels.is_empty() {
+        true => String::new(),
+        false => format!(
+            "<g data-testid=\"user-contrib-weekdays\">\n{}\n</g>\n",
+            calendar
+                .weekday_labels
+                .iter()
+                .map(|label| render_text(label, 0, top))
+                .collect::<Vec<_>>()
                 .join("\n")
-        )
+        ),
+    };
+
+    format!(
+        r#"{weekdays}<g transform="translate({}, 0)">
+{grid}
+</g>"#,
+        calendar.grid_x
+    )
+}
+
+/// The element name and geometry attributes of a cell with its top left corner at `(x, y)`
+fn cell_shape(layout: &CalendarLayout, x: usize, y: usize) -> (&'static str, String) {
+    let size = layout.cell_size;
+    let radius = layout.cell_radius;
+    let half = size as f64 / 2.0;
+    let (x, y) = (x as f64, y as f64);
+
+    match layout.cell_shape {
+        CellShape::Square => (
+            "rect",
+            format!(r#"x="{x}" y="{y}" width="{size}" height="{size}""#),
+        ),
+        CellShape::Rounded => (
+            "rect",
+            format!(
+                r#"x="{x}" y="{y}" rx="{radius}" ry="{radius}" width="{size}" height="{size}""#
+            ),
+        ),
+        CellShape::Circle => (
+            "circle",
+            format!(r#"cx="{}" cy="{}" r="{half}""#, x + half, y + half),
+        ),
+        CellShape::Hexagon => {
+            let size = size as f64;
+            let points = [
+                (half, 0.0),
+                (size, size / 4.0),
+                (size, size * 3.0 / 4.0),
+                (half, size),
+                (0.0, size * 3.0 / 4.0),
+                (0.0, size / 4.0),
+            ]
+            .map(|(dx, dy)| format!("{},{}", x + dx, y + dy))
+            .join(" ");
+            ("polygon", format!(r#"points="{points}""#))
+        }
+    }
+}
+
+fn render_weeks(
+    calendar: &Calendar,
+    top: usize,
+    layout: &CalendarLayout,
+    animations: &BTreeMap<NaiveDate, CellAnimation>,
+) -> String {
+    calendar
+        .weeks
+        .iter()
+        .map(|week| {
+            let cells = week
+                .days
+                .iter()
+                .map(|day| {
+                    let hover_info = &day.description;
+                    let data_date = day.date.to_string();
+                    let (element, geometry) = cell_shape(layout, 0, day.y - week.y);
+                    let (fill, gradient) = match render_source_gradient(day, layout) {
+                        None => (day.colour.to_string(), String::new()),
+                        Some((id, gradient)) => (format!("url(#{id})"), gradient),
+                    };
+                    let mut style = vec![];
+                    if let Some(dark_colour) = &day.dark_colour {
+                        style.push(match gradient.is_empty() {
+                            true => format!("--dark-fill: {dark_colour}"),
+                            false => format!("--dark-fill: {fill}"),
+                        });
+                    }
+                    let mut class = "user-contrib-cell has-tooltip".to_owned();
+                    if let Some(animation) = animations.get(&day.date) {
+                        class = format!("{class} {}", animation.class);
+                        if let Some(delay) = animation.delay {
+                            style.push(format!("animation-delay: {delay:.2}s"));
+                        }
+                    }
+                    let style = match style.is_empty() {
+                        true => String::new(),
+                        false => format!(r#" style="{}""#, style.join("; ")),
+                    };
+                    format!(r#"{gradient}<{element} {geometry} fill="{fill}"{style} data-hover-info="{hover_info}" data-date="{data_date}" class="{class}"><title>{}</title></{element}>"#, escape(&day.tooltip))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!(
+                r#"<g transform="translate({}, {})" data-testid="user-contrib-cell-group">
+{cells}
+</g>"#,
+                week.x - calendar.grid_x,
+                week.y - top
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render the annotations of `calendar` over its cells relative to the top edge `top`.
+/// They let the pointer through, so that the tooltips of the cells stay reachable.
+fn render_annotations(calendar: &Calendar, top: usize, layout: &CalendarLayout) -> String {
+    if calendar.annotations.is_empty() {
+        return String::new();
+    }
+
+    let font_colour = &layout.font_colour;
+    let marker = layout.cell_size as f64 / 4.0;
+    let annotations = calendar
+        .annotations
+        .iter()
+        .map(|annotation| {
+            let (class, attributes) = match annotation.kind {
+                AnnotationKind::Streak => (
+                    "user-contrib-streak",
+                    format!(r#"fill="none" stroke="{font_colour}" stroke-width="1.5" stroke-dasharray="3 2""#),
+                ),
+                AnnotationKind::Today => (
+                    "user-contrib-today",
+                    format!(r#"fill="none" stroke="{font_colour}" stroke-width="2""#),
+                ),
+                AnnotationKind::Holiday => (
+                    "user-contrib-holiday",
+                    r#"fill="url(#user-contrib-hatch)""#.to_owned(),
+                ),
+                AnnotationKind::Label => ("user-contrib-label", format!(r#"fill="{font_colour}""#)),
+            };
+            let cells = annotation
+                .cells
+                .iter()
+                .map(|&(x, y)| {
+                    let (x, y) = (x - calendar.grid_x, y - top);
+                    match annotation.kind {
+                        // A dot in the top right corner
+                        AnnotationKind::Label => format!(
+                            r#"<circle cx="{}" cy="{}" r="{marker}"></circle>"#,
+                            (x + layout.cell_size) as f64 - marker,
+                            y as f64 + marker
+                        ),
+                        _ => {
+                            let (element, geometry) = cell_shape(layout, x, y);
+                            format!("<{element} {geometry}></{element}>")
+                        }
+                    }
+                })
+                .collect::<String>();
+            format!(
+                r#"<g {attributes} class="{class}" data-testid="{class}"><title>{}</title>{cells}</g>"#,
+                escape(&annotation.text)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("\n<g pointer-events=\"none\">\n{annotations}\n</g>")
+}
+
+/// A gradient with hard stops dividing the cell of an active day between its sources,
+/// returned with its ID
+fn render_source_gradient(day: &Day, layout: &CalendarLayout) -> Option<(String, String)> {
+    if day.count == 0 || day.source_counts.is_empty() {
+        return None;
+    }
+
+    let id = format!("user-contrib-sources-{}", day.date);
+    let direction = match layout.source_split {
+        SourceSplit::Stacked => r#"x2="0" y2="1""#,
+        SourceSplit::Stripes => r#"x2="1" y2="0""#,
+    };
+    let mut start = 0.0;
+    let mut stops = String::new();
+    for (end, source) in day.source_shares().into_iter().zip(&layout.sources) {
+        if end > start {
+            stops += &format!(
+                r#"<stop offset="{start:.3}" stop-color="{0}"></stop><stop offset="{end:.3}" stop-color="{0}"></stop>"#,
+                source.colour
+            );
+            start = end;
+        }
     }
 
-    fn get_style(&self) -> String {
-        format!(
-            r#"<style>
+    let gradient = format!(r#"<linearGradient id="{id}" {direction}>{stops}</linearGradient>"#);
+    Some((id, gradient))
+}
+
+fn render_source_legend(legend: &SourceLegend, layout: &CalendarLayout) -> String {
+    legend
+        .entries
+        .iter()
+        .map(|(cell, label)| {
+            let (element, geometry) = cell_shape(layout, cell.x, cell.y - legend.y);
+            format!(
+                r#"<{element} {geometry} fill="{}" class="user-contrib-source-cell"></{element}>
+{}"#,
+                cell.colour,
+                render_text(label, 0, legend.y)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_legend(legend: &Legend, layout: &CalendarLayout) -> String {
+    let cells = legend
+        .cells
+        .iter()
+        .map(|cell| {
+            let dark_fill = dark_fill(cell.dark_colour.as_ref());
+            let (element, geometry) = cell_shape(layout, cell.x, cell.y - legend.y);
+            format!(
+                r#"<{element} {geometry} fill="{}"{dark_fill} class="user-contrib-legend-cell"></{element}>"#,
+                cell.colour
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{}\n{cells}\n{}",
+        render_text(&legend.less, 0, legend.y),
+        render_text(&legend.more, 0, legend.y)
+    )
+}
+
+fn wrap_svg(layout: &CalendarLayout, content: &str, animation: Option<Animation>) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" role="img" aria-label="{}" class="contrib-calendar" data-testid="contrib-calendar">
+    <title>{}</title>
+    <desc>{}</desc>
+    {}{}
+    {}
+</svg>"#,
+        layout.width,
+        layout.height,
+        layout.summary.label,
+        layout.summary.title,
+        layout.summary.description,
+        get_style(layout),
+        get_animation_style(animation),
+        content
+    )
+}
+
+/// The keyframes of `animation`, which only run if the viewer does not prefer reduced motion.
+/// The cells are fully visible outside of the animation, so that it can be ignored safely.
+fn get_animation_style(animation: Option<Animation>) -> String {
+    let rules = match animation {
+        None => return String::new(),
+        Some(Animation::Fill) => {
+            r#"@keyframes user-contrib-fill {
+                    from {
+                        opacity: 0;
+                    }
+                }
+
+                .user-contrib-fill {
+                    animation: user-contrib-fill 0.4s ease-out backwards;
+                }"#
+        }
+        Some(Animation::Pulse) => {
+            r#"@keyframes user-contrib-pulse {
+                    50% {
+                        opacity: 0.4;
+                    }
+                }
+
+                .user-contrib-pulse {
+                    animation: user-contrib-pulse 1.6s ease-in-out infinite;
+                }"#
+        }
+    };
+
+    format!(
+        r#"
+    <style>
+            @media (prefers-reduced-motion: no-preference) {{
+                {rules}
+            }}
+        </style>"#
+    )
+}
+
+fn get_style(layout: &CalendarLayout) -> String {
+    let dark = match &layout.dark_font_colour {
+        None => String::new(),
+        Some(dark_font_colour) => format!(
+            r#"
+
+            @media (prefers-color-scheme: dark) {{
+                .user-contrib-text {{
+                    fill: {dark_font_colour};
+                }}
+
+                .user-contrib-cell, .user-contrib-legend-cell {{
+                    fill: var(--dark-fill);
+                }}{}
+            }}"#,
+            match layout
+                .calendars
+                .iter()
+                .all(|calendar| calendar.annotations.is_empty())
+            {
+                true => String::new(),
+                false => format!(
+                    r#"
+
+                .user-contrib-streak, .user-contrib-today, .user-contrib-hatch {{
+                    stroke: {dark_font_colour};
+                }}
+
+                .user-contrib-label {{
+                    fill: {dark_font_colour};
+                }}"#
+                ),
+            }
+        ),
+    };
+
+    format!(
+        r#"<style>
             :root {{
                 --text-color-default: #3a383f;
                 --border-color-default: #dcdcde;
@@ -264,73 +1663,601 @@ impl SvgRenderer {
                 font-size: {}px;
                 font-family: "Noto Sans", Ubuntu, Cantarell, "Helvetica Neue", sans-serif;
                 fill: {};
-            }}
+            }}{dark}
         </style>"#,
-            self.font_size, self.font_colour,
-        )
-    }
+        layout.font_size, layout.font_colour,
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Builder, SvgRenderer};
-    use crate::{provider::github::Github, source::FixtureDataSource, svg::svg_renderer::Data};
-    use chrono::NaiveDate;
+    use super::{
+        Anchor, AnnotationKind, Builder, BuilderError, Calendar, ColourStrategy, Direction,
+        Renderer, SvgRenderer,
+    };
+    use crate::svg::{html_renderer::HtmlRenderer, isometric_renderer::IsometricRenderer};
+    use crate::{
+        provider::github::Github,
+        source::ReplayDataSource,
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::{Days, NaiveDate};
+    use std::collections::BTreeMap;
 
     #[tokio::test]
     async fn render_full() {
-        let activity = Github::fetch(FixtureDataSource::GithubUser, "".into())
+        let activity = Github::fetch(ReplayDataSource::fixtures(), "thomas-zahner".into())
             .await
             .unwrap();
 
         let today = chrono::naive::NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
 
-        let svg = get_renderer().render_at(&activity, today);
+        let svg = get_renderer().render_range(&activity, &DateRange::year_until(today));
         let fixture = read_fixture("fixtures/activity.svg");
         assert_eq!(svg, fixture.trim());
     }
 
     #[test]
-    fn render_week() {
-        let data = vec![vec![
-            Data {
-                count: 0,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 2).unwrap(),
-            },
-            Data {
-                count: 0,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 3).unwrap(),
-            },
-            Data {
-                count: 1,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 4).unwrap(),
-            },
-            Data {
-                count: 2,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 5).unwrap(),
-            },
-            Data {
-                count: 17,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 6).unwrap(),
-            },
-            Data {
-                count: 0,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 7).unwrap(),
-            },
-            Data {
-                count: 0,
-                date: NaiveDate::from_ymd_opt(2024, 12.try_into().unwrap(), 8).unwrap(),
-            },
-        ]];
+    fn render_quarter() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let range = DateRange::new(from, to).unwrap();
+
+        let svg = get_renderer().render_range(&ContributionActivity::new(), &range);
+        assert_eq!(svg.matches("<rect").count(), 91);
+        assert!(svg.contains(r#"data-date="2024-01-01""#));
+        assert!(svg.contains(r#"data-date="2024-03-31""#));
+        assert!(!svg.contains(r#"data-date="2024-04-01""#));
+        assert!(!svg.contains(">Apr<"));
+    }
 
-        let svg = get_renderer().render_week_rows(data);
+    #[test]
+    fn render_years() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([
+            (date(2022, 6, 1), 1),
+            (date(2023, 6, 1), 2),
+            (date(2023, 6, 2), 3),
+        ]));
+        let range = DateRange::new(date(2022, 1, 1), date(2023, 12, 31)).unwrap();
+
+        let svg = get_renderer().render_years(&activity, &range);
+        assert_eq!(svg.matches(r#"data-testid="user-contrib-year""#).count(), 2);
+        assert_eq!(svg.matches("<rect").count(), 365 * 2);
+        assert!(svg.contains(">2022: 1 contribution<"));
+        assert!(svg.contains(">2023: 5 contributions<"));
+        assert!(svg.contains(r#"<g transform="translate(0, 0)" data-testid="user-contrib-year">"#));
+        assert!(
+            svg.contains(r#"<g transform="translate(0, 146)" data-testid="user-contrib-year">"#)
+        );
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="854" height="292""#)
+        );
+    }
+
+    #[test]
+    fn render_week() {
+        let first_day = NaiveDate::from_ymd_opt(2024, 12, 2).unwrap();
+        let svg = render_week_cells(&get_renderer(), first_day);
         let fixture = read_fixture("fixtures/week_group.svg");
         assert_eq!(svg, fixture.trim());
     }
 
+    #[test]
+    fn render_week_starting_sunday() {
+        let first_day = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+        let svg = render_week_cells(&get_renderer_starting_on("Sun"), first_day);
+        let fixture = read_fixture("fixtures/week_group_sunday.svg");
+        assert_eq!(svg, fixture.trim());
+    }
+
+    #[test]
+    fn render_week_starting_saturday() {
+        let first_day = NaiveDate::from_ymd_opt(2024, 11, 30).unwrap();
+        let svg = render_week_cells(&get_renderer_starting_on("saturday"), first_day);
+        let fixture = read_fixture("fixtures/week_group_saturday.svg");
+        assert_eq!(svg, fixture.trim());
+    }
+
+    #[tokio::test]
+    async fn render_full_starting_sunday() {
+        let activity = Github::fetch(ReplayDataSource::fixtures(), "thomas-zahner".into())
+            .await
+            .unwrap();
+
+        let today = chrono::naive::NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+
+        let svg = get_renderer_starting_on("Sunday")
+            .render_range(&activity, &DateRange::year_until(today));
+        let fixture = read_fixture("fixtures/activity_sunday.svg");
+        assert_eq!(svg, fixture.trim());
+    }
+
+    #[tokio::test]
+    async fn render_full_decorated() {
+        let activity = Github::fetch(ReplayDataSource::fixtures(), "thomas-zahner".into())
+            .await
+            .unwrap();
+
+        let today = chrono::naive::NaiveDate::from_ymd_opt(2024, 12, 13).unwrap();
+        let renderer = Builder {
+            weekday_labels: Some(true),
+            legend: Some(true),
+            caption: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let svg = renderer.render_range(&activity, &DateRange::year_until(today));
+        let fixture = read_fixture("fixtures/activity_decorated.svg");
+        assert_eq!(svg, fixture.trim());
+    }
+
+    #[test]
+    fn caption() {
+        let renderer = Builder {
+            caption: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let date = |month, day| NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(3, 1), 1234)]));
+
+        let svg = renderer.render_range(&activity, &DateRange::calendar_year(2023).unwrap());
+        assert!(svg.contains(">1,234 contributions in 2023</text>"));
+
+        let range = DateRange::new(date(1, 1), date(3, 31)).unwrap();
+        let svg = renderer.render_range(&activity, &range);
+        assert!(svg.contains(">1,234 contributions from 2023-01-01 to 2023-03-31</text>"));
+
+        let svg = renderer.render(&ContributionActivity::new());
+        assert!(svg.contains(">No contributions in the last year</text>"));
+    }
+
+    #[test]
+    fn accessibility() {
+        let date = |day| NaiveDate::from_ymd_opt(2023, 3, day).unwrap();
+        let activity =
+            ContributionActivity::from(BTreeMap::from([(date(1), 2), (date(2), 0), (date(3), 1)]));
+        let range = DateRange::new(date(1), date(3)).unwrap();
+
+        let svg = get_renderer().render_range(&activity, &range);
+        assert!(svg.contains(r#"role="img" aria-label="Contribution activity: 3 contributions from 2023-03-01 to 2023-03-03""#));
+        assert!(svg.contains("<title>Contribution activity</title>"));
+        assert!(svg.contains("<desc>3 contributions from 2023-03-01 to 2023-03-03 on 2 active days. The busiest day was 2023-03-01 with 2 contributions.</desc>"));
+        assert!(svg.contains("<title>No contributions on 2023-03-02</title></rect>"));
+        assert!(svg.contains("<title>1 contribution on 2023-03-03</title></rect>"));
+    }
+
+    #[test]
+    fn locale() {
+        let renderer = Builder {
+            locale: Some("de-AT".into()),
+            weekday_labels: Some(true),
+            legend: Some(true),
+            caption: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let date = |month, day| NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(3, 1), 1234)]));
+
+        let svg = renderer.render_range(&activity, &DateRange::calendar_year(2023).unwrap());
+        assert!(svg.contains(">1.234 Beiträge im Jahr 2023</text>"));
+        assert!(svg.contains(">Mär</text>"));
+        assert!(svg.contains(">Mo</text>"));
+        assert!(svg.contains(">Weniger</text>"));
+        assert!(svg.contains("<title>Keine Beiträge am 2023-03-02</title>"));
+        assert!(svg.contains(r#"aria-label="Beitragsaktivität: 1.234 Beiträge im Jahr 2023""#));
+
+        let unknown = Builder {
+            locale: Some("tlh".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            unknown.build().err(),
+            Some(BuilderError::UnknownLocale("tlh".into()))
+        );
+    }
+
+    #[test]
+    fn right_to_left() {
+        let builder = |locale: &str| Builder {
+            locale: Some(locale.into()),
+            weekday_labels: Some(true),
+            legend: Some(true),
+            ..Default::default()
+        };
+        let range = DateRange::calendar_year(2023).unwrap();
+        let activity = ContributionActivity::new();
+        let ltr = builder("en").build().unwrap().layout(&activity, &range);
+        let rtl = builder("he").build().unwrap().layout(&activity, &range);
+
+        assert_eq!(rtl.direction, Direction::Rtl);
+        assert_eq!((rtl.width, rtl.height), (ltr.width, ltr.height));
+        for (ltr_day, rtl_day) in ltr.days().zip(rtl.days()) {
+            assert_eq!(rtl_day.x, ltr.width - ltr_day.x - ltr.cell_size);
+            assert_eq!(rtl_day.y, ltr_day.y);
+        }
+        let first_day = rtl.days().next().unwrap();
+        let last_day = rtl.days().last().unwrap();
+        assert!(first_day.x > last_day.x);
+
+        let weekday = &rtl.calendars[0].weekday_labels[0];
+        assert_eq!(weekday.anchor, Anchor::Left);
+        assert!(weekday.x > first_day.x);
+
+        let svg = builder("ar")
+            .build()
+            .unwrap()
+            .render_range(&activity, &range);
+        assert!(svg.contains(r#"text-anchor="end" class="user-contrib-text">يناير</text>"#));
+    }
+
+    #[test]
+    fn cell_shapes() {
+        let date = NaiveDate::from_ymd_opt(2023, 3, 6).unwrap();
+        let range = DateRange::new(date, date).unwrap();
+        let render = |shape: &str| {
+            Builder {
+                cell_shape: Some(shape.into()),
+                cell_size: Some(8),
+                legend: Some(true),
+                ..Default::default()
+            }
+            .build()
+            .unwrap()
+            .render_range(&ContributionActivity::new(), &range)
+        };
+
+        assert!(
+            render("rounded").contains(r#"<rect x="0" y="0" rx="2" ry="2" width="8" height="8""#)
+        );
+        assert!(render("Square").contains(r#"<rect x="0" y="0" width="8" height="8""#));
+        let circle = render("circle");
+        assert!(circle.contains(r#"<circle cx="4" cy="4" r="4""#));
+        assert!(circle.contains("</circle>"));
+        assert!(!circle.contains("<rect"));
+        assert!(render("hexagon").contains(r#"<polygon points="4,0 8,2 8,6 4,8 0,6 0,2""#));
+
+        assert_eq!(
+            Builder {
+                cell_shape: Some("star".into()),
+                ..Default::default()
+            }
+            .build()
+            .err(),
+            Some(BuilderError::UnknownCellShape("star".into()))
+        );
+    }
+
+    #[test]
+    fn cell_spacing() {
+        let renderer = Builder {
+            cell_size: Some(10),
+            cell_radius: Some(5),
+            cell_gap: Some(4),
+            margin: Some(20),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let from = NaiveDate::from_ymd_opt(2023, 3, 6).unwrap();
+        let range = DateRange::new(from, from.checked_add_days(Days::new(13)).unwrap()).unwrap();
+        let layout = renderer.layout(&ContributionActivity::new(), &range);
+
+        let weeks = &layout.calendars[0].weeks;
+        assert_eq!(weeks[0].x, 20);
+        assert_eq!(weeks[1].x, 20 + 14);
+        assert_eq!(weeks[0].days[1].y - weeks[0].days[0].y, 14);
+        assert_eq!(layout.width, 20 + 2 * 14);
+        assert_eq!(layout.cell_radius, 5);
+        assert!(renderer
+            .render_range(&ContributionActivity::new(), &range)
+            .contains(r#"rx="5" ry="5""#));
+    }
+
+    #[test]
+    fn legend_without_margin() {
+        let renderer = Builder {
+            font_size: Some(0),
+            margin: Some(0),
+            legend: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let layout = renderer.layout(&ContributionActivity::new(), &DateRange::last_days(14));
+
+        let legend = layout.legend.unwrap();
+        assert_eq!(legend.less.x, 0);
+        assert_eq!(legend.cells[0].x, 0);
+    }
+
+    #[test]
+    fn sources() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let sources = [
+            (
+                "github.com".to_owned(),
+                ContributionActivity::from(BTreeMap::from([(date(4), 1), (date(5), 2)])),
+            ),
+            (
+                "gitlab.com".to_owned(),
+                ContributionActivity::from(BTreeMap::from([(date(4), 3)])),
+            ),
+        ];
+        let renderer = Builder {
+            legend: Some(true),
+            source_split: Some("stripes".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let range = DateRange::new(date(4), date(6)).unwrap();
+        let layout = renderer.layout_sources(&sources, &range);
+
+        let days: Vec<_> = layout.days().collect();
+        assert_eq!(days[0].count, 4);
+        assert_eq!(days[0].source_counts, vec![1, 3]);
+        assert_eq!(days[0].source_shares(), vec![0.25, 1.0]);
+        assert_eq!(days[2].source_counts, vec![0, 0]);
+        assert_eq!(layout.sources[1].name, "gitlab.com");
+        assert!(layout.legend.is_none());
+        assert_eq!(layout.source_legend.as_ref().unwrap().entries.len(), 2);
+
+        let svg = renderer.render_layout(&layout);
+        assert!(svg.contains(r##"<linearGradient id="user-contrib-sources-2024-03-04" x2="1" y2="0"><stop offset="0.000" stop-color="#4e79a7ff"></stop><stop offset="0.250" stop-color="#4e79a7ff"></stop><stop offset="0.250" stop-color="#f28e2bff"></stop><stop offset="1.000" stop-color="#f28e2bff"></stop></linearGradient>"##));
+        // A day of a single source is filled with its colour alone
+        assert!(svg.contains(r##"<stop offset="0.000" stop-color="#4e79a7ff"></stop><stop offset="1.000" stop-color="#4e79a7ff"></stop></linearGradient>"##));
+        assert!(!svg.contains("user-contrib-sources-2024-03-06"));
+        assert!(svg.contains(">gitlab.com</text>"));
+
+        let years = renderer.layout_years_sources(&sources, &range);
+        assert_eq!(years.days().next().unwrap().source_counts, vec![1, 3]);
+
+        let invalid = Builder {
+            source_split: Some("checkered".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            invalid.build().err(),
+            Some(BuilderError::UnknownSourceSplit("checkered".into()))
+        );
+    }
+
+    #[test]
+    fn comparison() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let rows = [
+            (
+                "alice".to_owned(),
+                ContributionActivity::from(BTreeMap::from([(date(4), 1), (date(5), 30)])),
+            ),
+            (
+                "bob".to_owned(),
+                ContributionActivity::from(BTreeMap::from([(date(4), 1)])),
+            ),
+        ];
+        let renderer = Builder {
+            colour_strategy: Some("InterpolationStrategy".into()),
+            inactive_colour: Some("#ffffff".into()),
+            active_colour: Some("#000000".into()),
+            scale: Some("linear".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let range = DateRange::new(date(1), date(31)).unwrap();
+        let layout = renderer.layout_comparison(&rows, &range);
+
+        let [alice, bob] = &layout.calendars[..] else {
+            panic!("expected one calendar per row");
+        };
+        assert_eq!(
+            alice.title.as_ref().unwrap().text,
+            "alice: 31 contributions"
+        );
+        assert_eq!(bob.title.as_ref().unwrap().text, "bob: 1 contribution");
+        assert!(!alice.month_labels.is_empty());
+        assert!(bob.month_labels.is_empty());
+        assert!(bob.height < alice.height);
+        assert_eq!(bob.y, alice.y + alice.height);
+
+        // Days are aligned and coloured on the same scale
+        let day = |calendar: &Calendar, date| {
+            calendar
+                .weeks
+                .iter()
+                .flat_map(|week| &week.days)
+                .find(|day| day.date == date)
+                .cloned()
+                .unwrap()
+        };
+        let (alice_day, bob_day) = (day(alice, date(4)), day(bob, date(4)));
+        assert_eq!(alice_day.x, bob_day.x);
+        assert_eq!(alice_day.colour, bob_day.colour);
+        let alone = renderer.layout(&rows[1].1, &range);
+        assert_ne!(alone.days().nth(3).unwrap().colour, bob_day.colour);
+    }
+
+    #[test]
+    fn escaped_names() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let rows = [(
+            r#"<script>alert("x & y")</script>"#.to_owned(),
+            ContributionActivity::from(BTreeMap::from([(date, 1)])),
+        )];
+        let layout = get_renderer().layout_comparison(&rows, &DateRange::new(date, date).unwrap());
+        let escaped = "&lt;script&gt;alert(&quot;x &amp; y&quot;)&lt;/script&gt;";
+
+        for output in [
+            get_renderer().render_layout(&layout),
+            IsometricRenderer::default().render_layout(&layout),
+            HtmlRenderer.render_layout(&layout),
+        ] {
+            assert!(output.contains(&format!("{escaped}: 1 contribution<")));
+            assert!(!output.contains("<script>"));
+        }
+    }
+
+    #[test]
+    fn annotations() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([
+            (date(4), 1),
+            (date(5), 2),
+            (date(6), 3),
+            (date(8), 1),
+            (date(9), 1),
+        ]));
+        let renderer = Builder {
+            longest_streak: Some(true),
+            today: Some(date(14)),
+            holidays: Some(
+                "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240311\nDTEND;VALUE=DATE:20240313\nSUMMARY:Leave\nEND:VEVENT"
+                    .into(),
+            ),
+            date_labels: Some(vec!["2024-03-05:v1.0 <beta>".into()]),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        let range = DateRange::new(date(1), date(31)).unwrap();
+        let layout = renderer.layout(&activity, &range);
+        let plain = Builder::default()
+            .build()
+            .unwrap()
+            .layout(&activity, &range);
+
+        let kinds = layout.calendars[0]
+            .annotations
+            .iter()
+            .map(|annotation| {
+                (
+                    annotation.kind,
+                    annotation.text.as_str(),
+                    annotation.cells.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (AnnotationKind::Holiday, "Leave", 2),
+                (
+                    AnnotationKind::Streak,
+                    "The longest streak lasted 3 days.",
+                    3
+                ),
+                (AnnotationKind::Label, "v1.0 <beta>", 1),
+                (AnnotationKind::Today, "2024-03-14", 1),
+            ]
+        );
+        let day = layout.days().find(|day| day.date == date(5)).unwrap();
+        assert_eq!(layout.calendars[0].annotations[1].cells[1], (day.x, day.y));
+        assert_eq!(day.tooltip, "2 contributions on 2024-03-05 · v1.0 <beta>");
+        // The colours stay the same
+        assert!(layout
+            .days()
+            .zip(plain.days())
+            .all(|(day, plain)| day.colour == plain.colour));
+
+        let svg = renderer.render_layout(&layout);
+        assert!(svg.contains(r#"<pattern id="user-contrib-hatch""#));
+        assert_eq!(svg.matches(r#"fill="url(#user-contrib-hatch)""#).count(), 1);
+        assert!(svg.contains(
+            r#"data-testid="user-contrib-streak"><title>The longest streak lasted 3 days.</title>"#
+        ));
+        assert!(svg.contains("<title>2 contributions on 2024-03-05 · v1.0 &lt;beta&gt;</title>"));
+        assert!(svg.contains(".user-contrib-label {"));
+        assert!(!get_renderer()
+            .render_layout(&plain)
+            .contains("user-contrib-streak"));
+
+        let error = |builder| Builder::build(builder).err();
+        assert_eq!(
+            error(Builder {
+                date_labels: Some(vec!["14.03.2024:Release".into()]),
+                ..Default::default()
+            }),
+            Some(BuilderError::InvalidDate("14.03.2024".into()))
+        );
+        assert_eq!(
+            error(Builder {
+                date_labels: Some(vec!["2024-03-05".into()]),
+                ..Default::default()
+            }),
+            Some(BuilderError::InvalidDateLabel("2024-03-05".into()))
+        );
+    }
+
+    #[test]
+    fn animation() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let activity =
+            ContributionActivity::from(BTreeMap::from([(date(1), 4), (date(3), 1), (date(4), 2)]));
+        let range = DateRange::new(date(1), date(5)).unwrap();
+        let render = |animation: &str| {
+            Builder {
+                animation: Some(animation.into()),
+                dark_theme: Some("github-dark".into()),
+                ..Default::default()
+            }
+            .build()
+            .unwrap()
+            .render_range(&activity, &range)
+        };
+
+        let svg = render("fill");
+        assert_eq!(svg.matches("user-contrib-fill\"").count(), 5);
+        assert!(svg.contains(r#"style="--dark-fill: #161b22ff; animation-delay: 0.40s""#));
+        assert!(svg.contains("animation-delay: 1.60s"));
+        assert!(svg.contains("@media (prefers-reduced-motion: no-preference)"));
+        assert!(svg.contains("@keyframes user-contrib-fill"));
+
+        // The last day is not over yet, so the streak of the two days before it continues
+        let svg = render("pulse");
+        assert_eq!(svg.matches("user-contrib-pulse\"").count(), 2);
+        assert!(svg.contains(
+            r#"data-date="2024-03-04" class="user-contrib-cell has-tooltip user-contrib-pulse""#
+        ));
+        assert!(!svg.contains("animation-delay"));
+
+        assert!(!get_renderer()
+            .render_range(&activity, &range)
+            .contains("@keyframes"));
+        assert_eq!(
+            Builder {
+                animation: Some("spin".into()),
+                ..Default::default()
+            }
+            .build()
+            .err(),
+            Some(BuilderError::UnknownAnimation("spin".into()))
+        );
+    }
+
+    #[test]
+    fn invalid_first_day_of_week() {
+        let result = Builder {
+            first_day_of_week: Some("Someday".to_string()),
+            ..Default::default()
+        }
+        .build();
+        assert_eq!(
+            result.err(),
+            Some(BuilderError::InvalidWeekday("Someday".to_string()))
+        );
+    }
+
     #[test]
     fn test_default_font_colour() {
-        let svg = get_renderer().get_style();
+        let svg = get_style(&get_renderer());
         let expected = r#"<style>
             :root {
                 --text-color-default: #3a383f;
@@ -355,7 +2282,7 @@ mod tests {
         .build()
         .unwrap();
 
-        let svg = renderer.get_style();
+        let svg = get_style(&renderer);
         let expected = r#"<style>
             :root {
                 --text-color-default: #3a383f;
@@ -371,10 +2298,265 @@ mod tests {
         assert_eq!(svg, expected.trim());
     }
 
+    #[test]
+    fn dark_mode() {
+        let renderer = Builder {
+            dark_inactive_colour: Some("#161b22".into()),
+            dark_active_colour: Some("#39d353".into()),
+            dark_font_colour: Some("#f0f6fc".into()),
+            legend: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let style = get_style(&renderer);
+        assert!(style.contains("@media (prefers-color-scheme: dark)"));
+        assert!(style.contains("fill: #f0f6fcff;"));
+        assert!(style.contains("fill: var(--dark-fill);"));
+
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date, 0)]));
+        let svg = renderer.render_range(&activity, &DateRange::new(date, date).unwrap());
+        assert!(svg.contains(r##"fill="#ececefff" style="--dark-fill: #161b22ff""##));
+        assert!(svg.contains(
+            r##"fill="#303470ff" style="--dark-fill: #39d353ff" class="user-contrib-legend-cell""##
+        ));
+    }
+
+    #[test]
+    fn dark_mode_colours_missing() {
+        let result = Builder {
+            dark_active_colour: Some("#39d353".into()),
+            ..Default::default()
+        }
+        .build();
+        assert_eq!(
+            result.err(),
+            Some(BuilderError::InterpolationParametersMissing)
+        );
+    }
+
+    #[test]
+    fn theme() {
+        let renderer = Builder {
+            theme: Some("halloween".into()),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date, 12)]));
+        let svg = renderer.render_range(&activity, &DateRange::new(date, date).unwrap());
+        assert!(svg.contains(r##"fill="#03001cff" style="--dark-fill: #39d353ff""##));
+
+        let unknown = Builder {
+            theme: Some("solarized".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            unknown.build().err(),
+            Some(BuilderError::UnknownTheme("solarized".into()))
+        );
+
+        let conflict = Builder {
+            theme: Some("github".into()),
+            colour_strategy: Some("GitlabStrategy".into()),
+            ..Default::default()
+        };
+        assert_eq!(conflict.build().err(), Some(BuilderError::ThemeConflict));
+    }
+
+    #[test]
+    fn colours_without_strategy() {
+        // The light palette keeps the default as before, while the dark palette infers interpolation
+        let renderer = Builder {
+            inactive_colour: Some("#ffffff".into()),
+            active_colour: Some("#000000".into()),
+            dark_inactive_colour: Some("#000000".into()),
+            dark_active_colour: Some("#ffffff".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        assert!(matches!(
+            renderer.colour_strategy,
+            ColourStrategy::GitlabStrategy
+        ));
+        assert!(matches!(
+            renderer.dark.unwrap().colour_strategy,
+            ColourStrategy::InterpolationStrategy { .. }
+        ));
+
+        // Options without a previous meaning select the strategy they belong to
+        let renderer = Builder {
+            inactive_colour: Some("#ffffff".into()),
+            active_colour: Some("#000000".into()),
+            colour_space: Some("oklab".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        assert!(matches!(
+            renderer.colour_strategy,
+            ColourStrategy::InterpolationStrategy { .. }
+        ));
+    }
+
+    #[test]
+    fn quantile_strategy() {
+        let colours = ["#eeeeee", "#cccccc", "#999999", "#666666", "#333333"];
+        let renderer = Builder {
+            colours: Some(colours.map(String::from).into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let date = |day| NaiveDate::from_ymd_opt(2023, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from_iter(
+            (1..=8).map(|day| (date(day), day as usize - 1)),
+        ));
+        let svg = renderer.render_range(&activity, &DateRange::new(date(1), date(8)).unwrap());
+
+        let fills: Vec<_> = svg
+            .lines()
+            .filter(|line| line.contains("data-date="))
+            .map(|line| line.split(r#"fill=""#).nth(1).unwrap()[..7].to_owned())
+            .collect();
+        assert_eq!(
+            fills,
+            [
+                "#eeeeee", "#cccccc", "#cccccc", "#999999", "#999999", "#666666", "#666666",
+                "#333333"
+            ]
+        );
+    }
+
+    #[test]
+    fn discrete_strategy() {
+        let builder = |thresholds: &[usize]| Builder {
+            colour_strategy: Some("DiscreteStrategy".into()),
+            colours: Some(vec!["#eeeeee".into(), "#999999".into(), "#333333".into()]),
+            thresholds: Some(thresholds.into()),
+            ..Default::default()
+        };
+
+        assert!(builder(&[1, 5]).build().is_ok());
+        assert_eq!(
+            builder(&[5, 1]).build().err(),
+            Some(BuilderError::InvalidThresholds)
+        );
+        assert_eq!(
+            builder(&[1, 5, 10]).build().err(),
+            Some(BuilderError::InvalidThresholds)
+        );
+
+        let single_colour = Builder {
+            colours: Some(vec!["#eeeeee".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            single_colour.build().err(),
+            Some(BuilderError::PaletteColoursMissing)
+        );
+    }
+
+    #[test]
+    fn interpolation_gradient() {
+        let renderer = Builder {
+            colour_strategy: Some("InterpolationStrategy".into()),
+            colours: Some(vec!["#ffff00".into(), "#ff0000".into(), "#800080".into()]),
+            colour_space: Some("oklch".into()),
+            legend: Some(true),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date, 0)]));
+        let svg = renderer.render_range(&activity, &DateRange::new(date, date).unwrap());
+        assert!(svg.contains(r##"fill="#ffff00ff" class="user-contrib-legend-cell""##));
+        assert!(svg.contains(r##"fill="#ff0000ff" class="user-contrib-legend-cell""##));
+        assert!(svg.contains(r##"fill="#800080ff" class="user-contrib-legend-cell""##));
+
+        let unknown = Builder {
+            inactive_colour: Some("#ffffff".into()),
+            active_colour: Some("#000000".into()),
+            colour_space: Some("cmyk".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            unknown.build().err(),
+            Some(BuilderError::UnknownColourSpace("cmyk".into()))
+        );
+    }
+
+    #[test]
+    fn interpolation_scale() {
+        let renderer = Builder {
+            inactive_colour: Some("#000000".into()),
+            active_colour: Some("#ffffff".into()),
+            scale: Some("linear".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        let date = |day| NaiveDate::from_ymd_opt(2023, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(1), 1), (date(2), 4)]));
+        let svg = renderer.render_range(&activity, &DateRange::new(date(1), date(2)).unwrap());
+        assert!(svg.contains(r##"fill="#404040ff""##));
+        assert!(svg.contains(r##"fill="#ffffffff""##));
+
+        let unknown = Builder {
+            inactive_colour: Some("#000000".into()),
+            active_colour: Some("#ffffff".into()),
+            scale: Some("cubic".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            unknown.build().err(),
+            Some(BuilderError::UnknownScale("cubic".into()))
+        );
+    }
+
     fn get_renderer() -> SvgRenderer {
         Builder::default().build().unwrap()
     }
 
+    fn get_renderer_starting_on(first_day_of_week: &str) -> SvgRenderer {
+        Builder {
+            first_day_of_week: Some(first_day_of_week.to_string()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+    }
+
+    /// Render the cells of a week starting on `first_day` with the same counts as `fixtures/week_group.svg`
+    fn render_week_cells(renderer: &SvgRenderer, first_day: NaiveDate) -> String {
+        let activity = ContributionActivity::from(
+            [0, 0, 1, 2, 17, 0, 0]
+                .into_iter()
+                .zip(first_day.iter_days())
+                .map(|(count, date)| (date, count))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        let last_day = first_day.checked_add_days(Days::new(6)).unwrap();
+        let layout = renderer.layout(&activity, &DateRange::new(first_day, last_day).unwrap());
+        super::render_weeks(&layout.calendars[0], 0, &layout, &BTreeMap::new())
+    }
+
+    fn get_style(renderer: &SvgRenderer) -> String {
+        let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let range = DateRange::new(date, date).unwrap();
+        super::get_style(&renderer.layout(&ContributionActivity::new(), &range))
+    }
+
     fn read_fixture(path: &str) -> String {
         std::fs::read_to_string(path).expect("Unable to read file")
     }
@@ -0,0 +1,409 @@
+use std::env;
+
+use super::layout::{Anchor, Calendar, CalendarLayout, CellShape, Direction, Week};
+use super::renderer::Renderer;
+use super::rgba::Rgba;
+
+const RESET: &str = "\x1b[0m";
+
+/// The colours a terminal can display
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColourMode {
+    /// 24-bit colours
+    #[default]
+    TrueColour,
+    /// The 256 colours of xterm
+    Ansi256,
+    /// The 16 standard colours, whose appearance depends on the terminal theme
+    Ansi16,
+}
+
+/// The 6 levels of each channel in the colour cube of the 256 colours
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The default xterm values of the 16 standard colours
+const ANSI_16: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+impl ColourMode {
+    /// Guess the supported colours from the `COLORTERM` and `TERM` environment variables
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        match (colorterm, term) {
+            (Some("truecolor" | "24bit"), _) => ColourMode::TrueColour,
+            (_, Some(term)) if term.contains("256color") => ColourMode::Ansi256,
+            _ => ColourMode::Ansi16,
+        }
+    }
+
+    /// The escape sequence setting the foreground to `colour` or the closest available colour
+    fn foreground(&self, colour: &Rgba) -> String {
+        let [r, g, b, _] = colour.channels();
+        match self {
+            ColourMode::TrueColour => format!("\x1b[38;2;{r};{g};{b}m"),
+            ColourMode::Ansi256 => format!("\x1b[38;5;{}m", ansi_256([r, g, b])),
+            ColourMode::Ansi16 => {
+                let index = nearest(&ANSI_16, [r, g, b]);
+                match index {
+                    0..8 => format!("\x1b[{}m", 30 + index),
+                    _ => format!("\x1b[{}m", 90 + index - 8),
+                }
+            }
+        }
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    (0..3).map(|i| (a[i].abs_diff(b[i]) as u32).pow(2)).sum()
+}
+
+fn nearest(palette: &[[u8; 3]], colour: [u8; 3]) -> usize {
+    (0..palette.len())
+        .min_by_key(|&i| distance(palette[i], colour))
+        .unwrap_or_default()
+}
+
+/// Pick the closer of the nearest colour in the 6×6×6 cube and the nearest of the 24 greys
+fn ansi_256(colour: [u8; 3]) -> usize {
+    let levels = CUBE_LEVELS.map(|level| [level; 3]);
+    let [r, g, b] = colour.map(|channel| nearest(&levels, [channel; 3]));
+    let cube = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+
+    let average = colour
+        .iter()
+        .map(|&channel| channel as usize)
+        .sum::<usize>()
+        / 3;
+    let grey_index = (average.saturating_sub(3) / 10).min(23);
+    let grey = [(8 + 10 * grey_index) as u8; 3];
+
+    match distance(colour, grey) < distance(colour, cube) {
+        true => 232 + grey_index,
+        false => 16 + 36 * r + 6 * g + b,
+    }
+}
+
+/// Draws the calendar with coloured Unicode blocks, two columns per week.
+/// Annotations such as holidays or the longest streak are left out, as a single character cannot show them next to the colour.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalRenderer {
+    pub colour_mode: ColourMode,
+    /// Use the dark palette, if one is configured, for terminals with a dark background
+    pub dark: bool,
+}
+
+impl Renderer for TerminalRenderer {
+    fn render_layout(&self, layout: &CalendarLayout) -> String {
+        // The weekday labels are right of the grid in right-to-left layouts
+        let indent = match layout.direction {
+            Direction::Ltr => layout.calendars.iter().map(indent).max().unwrap_or(0),
+            Direction::Rtl => 0,
+        };
+        let mut lines = vec![];
+
+        if let Some(caption) = &layout.caption {
+            lines.push(caption.text.clone());
+        }
+
+        for (i, calendar) in layout.calendars.iter().enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.extend(self.render_calendar(calendar, indent, layout));
+        }
+
+        if let Some(legend) = &layout.legend {
+            let mut items: Vec<(usize, String)> = legend
+                .cells
+                .iter()
+                .map(|cell| {
+                    let text = self.render_cell(&cell.colour, cell.dark_colour.as_ref(), layout);
+                    (cell.x, text)
+                })
+                .chain(
+                    [&legend.less, &legend.more].map(|label| (label.x, format!("{} ", label.text))),
+                )
+                .collect();
+            items.sort_by_key(|(x, _)| *x);
+
+            let items: String = items.into_iter().map(|(_, text)| text).collect();
+            lines.push(format!("{}{}", " ".repeat(indent), items.trim_end()));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl TerminalRenderer {
+    fn render_cell(
+        &self,
+        colour: &Rgba,
+        dark_colour: Option<&Rgba>,
+        layout: &CalendarLayout,
+    ) -> String {
+        let colour = match (self.dark, dark_colour) {
+            (true, Some(dark_colour)) => dark_colour,
+            _ => colour,
+        };
+        let cell = match layout.cell_shape {
+            CellShape::Square | CellShape::Rounded => '■',
+            CellShape::Circle => '●',
+            CellShape::Hexagon => '⬢',
+        };
+        format!("{}{cell}{RESET} ", self.colour_mode.foreground(colour))
+    }
+
+    fn render_calendar(
+        &self,
+        calendar: &Calendar,
+        indent: usize,
+        layout: &CalendarLayout,
+    ) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(title) = &calendar.title {
+            lines.push(title.text.clone());
+        }
+
+        // Columns from left to right, which runs backwards in time for right-to-left layouts
+        let mut weeks: Vec<&Week> = calendar.weeks.iter().collect();
+        weeks.sort_by_key(|week| week.x);
+
+        let mut month_labels: Vec<(usize, &str)> = calendar
+            .month_labels
+            .iter()
+            .filter_map(|label| {
+                let column = weeks
+                    .iter()
+                    .position(|week| week.x <= label.x && label.x <= week.x + layout.cell_size)?;
+                let position = indent + column * 2;
+                let length = label.text.chars().count();
+                match label.anchor {
+                    Anchor::Left => Some((position, label.text.as_str())),
+                    Anchor::Right => {
+                        Some(((position + 2).saturating_sub(length), label.text.as_str()))
+                    }
+                }
+            })
+            .collect();
+        month_labels.sort_by_key(|(position, _)| *position);
+
+        let mut months = " ".repeat(indent);
+        for (position, text) in month_labels {
+            let length = months.chars().count();
+            // Skip labels overlapping the previous one, such as after a short first week
+            if position >= length {
+                months += &" ".repeat(position - length);
+                months += text;
+                months.push(' ');
+            }
+        }
+        // Rows of a comparison share the month labels of the first one
+        if !calendar.month_labels.is_empty() {
+            lines.push(months.trim_end().to_owned());
+        }
+
+        for row in 0..7 {
+            let label = calendar
+                .weekday_labels
+                .iter()
+                .find(|label| row_of(calendar, label.y, layout.cell_size) == Some(row))
+                .map_or("", |label| label.text.as_str());
+            let mut line = match indent {
+                0 => String::new(),
+                _ => format!("{label:>0$} ", indent - 1),
+            };
+
+            for week in &weeks {
+                match week.days.iter().find(|day| day.row == row) {
+                    Some(day) => {
+                        line += &self.render_cell(&day.colour, day.dark_colour.as_ref(), layout)
+                    }
+                    None => line += "  ",
+                }
+            }
+            if layout.direction == Direction::Rtl {
+                line += label;
+            }
+            lines.push(line.trim_end().to_owned());
+        }
+
+        lines
+    }
+}
+
+/// The width of the weekday labels including a space
+fn indent(calendar: &Calendar) -> usize {
+    calendar
+        .weekday_labels
+        .iter()
+        .map(|label| label.text.chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// The row of the cells at the vertical position `y`
+fn row_of(calendar: &Calendar, y: usize, cell_size: usize) -> Option<usize> {
+    calendar
+        .weeks
+        .iter()
+        .flat_map(|week| &week.days)
+        .find(|day| day.y <= y && y < day.y + cell_size)
+        .map(|day| day.row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ansi_256, ColourMode, TerminalRenderer};
+    use crate::{
+        svg::{renderer::Renderer, rgba::Rgba, svg_renderer::Builder},
+        types::{ContributionActivity, DateRange},
+    };
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    fn render(renderer: TerminalRenderer) -> String {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([
+            (date(1, 3), 1),
+            (date(1, 4), 4),
+            (date(2, 14), 12),
+            (date(3, 1), 2),
+            (date(3, 2), 7),
+        ]));
+        let layout = Builder {
+            weekday_labels: Some(true),
+            legend: Some(true),
+            caption: Some(true),
+            dark_theme: Some("github-dark".into()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &DateRange::new(date(1, 1), date(3, 31)).unwrap());
+
+        renderer.render_layout(&layout)
+    }
+
+    #[test]
+    fn render_true_colour() {
+        let output = render(TerminalRenderer::default());
+        let fixture = read_fixture("fixtures/terminal.txt");
+        assert_eq!(output, fixture);
+    }
+
+    #[test]
+    fn render_dark_16_colours() {
+        let output = render(TerminalRenderer {
+            colour_mode: ColourMode::Ansi16,
+            dark: true,
+        });
+        let fixture = read_fixture("fixtures/terminal_dark_16.txt");
+        assert_eq!(output, fixture);
+    }
+
+    #[test]
+    fn render_years() {
+        let date = |year| NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+        let range = DateRange::new(date(2022), date(2023)).unwrap();
+        let layout = Builder::default()
+            .build()
+            .unwrap()
+            .layout_years(&ContributionActivity::new(), &range);
+
+        let output = TerminalRenderer::default().render_layout(&layout);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 1 + 1 + 7 + 1 + 1 + 1 + 7);
+        assert_eq!(lines[0], "2022: no contributions");
+        assert_eq!(lines[10], "2023: no contributions");
+        assert!(lines[11].starts_with("Jan ") && lines[11].ends_with(" Dec"));
+    }
+
+    #[test]
+    fn render_without_annotations() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let activity = ContributionActivity::from(BTreeMap::from([(date(4), 1), (date(5), 2)]));
+        let range = DateRange::new(date(1), date(31)).unwrap();
+        let annotated = Builder {
+            longest_streak: Some(true),
+            today: Some(date(14)),
+            date_labels: Some(vec!["2024-03-05:v1.0".into()]),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+        .layout(&activity, &range);
+        let plain = Builder::default()
+            .build()
+            .unwrap()
+            .layout(&activity, &range);
+
+        assert!(!annotated.calendars[0].annotations.is_empty());
+        assert_eq!(
+            TerminalRenderer::default().render_layout(&annotated),
+            TerminalRenderer::default().render_layout(&plain)
+        );
+    }
+
+    #[test]
+    fn ansi_256_colours() {
+        assert_eq!(ansi_256([0, 0, 0]), 16);
+        assert_eq!(ansi_256([255, 255, 255]), 231);
+        assert_eq!(ansi_256([255, 0, 0]), 196);
+        assert_eq!(ansi_256([128, 128, 128]), 244);
+        assert_eq!(
+            ColourMode::Ansi256.foreground(&Rgba::new(0, 135, 0, 255)),
+            "\x1b[38;5;28m"
+        );
+    }
+
+    #[test]
+    fn ansi_16_colours() {
+        let foreground = |r, g, b| ColourMode::Ansi16.foreground(&Rgba::new(r, g, b, 255));
+        assert_eq!(foreground(10, 10, 10), "\x1b[30m");
+        assert_eq!(foreground(200, 20, 20), "\x1b[31m");
+        assert_eq!(foreground(250, 250, 250), "\x1b[97m");
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            ColourMode::from_env(Some("truecolor"), Some("xterm")),
+            ColourMode::TrueColour
+        );
+        assert_eq!(
+            ColourMode::from_env(None, Some("xterm-256color")),
+            ColourMode::Ansi256
+        );
+        assert_eq!(
+            ColourMode::from_env(None, Some("linux")),
+            ColourMode::Ansi16
+        );
+        assert_eq!(ColourMode::from_env(None, None), ColourMode::Ansi16);
+    }
+
+    fn read_fixture(path: &str) -> String {
+        std::fs::read_to_string(path).expect("Unable to read file")
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fmt, str::FromStr};
+
+use super::{contribution_colour::ColourStrategy, rgba::Rgba};
+
+/// Predefined discrete palettes which can be selected by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Github,
+    GithubDark,
+    Gitlab,
+    ColourBlind,
+    HighContrast,
+    Halloween,
+    Winter,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 7] = [
+        Theme::Github,
+        Theme::GithubDark,
+        Theme::Gitlab,
+        Theme::ColourBlind,
+        Theme::HighContrast,
+        Theme::Halloween,
+        Theme::Winter,
+    ];
+
+    /// The name used to select the theme
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Github => "github",
+            Theme::GithubDark => "github-dark",
+            Theme::Gitlab => "gitlab",
+            Theme::ColourBlind => "colour-blind",
+            Theme::HighContrast => "high-contrast",
+            Theme::Halloween => "halloween",
+            Theme::Winter => "winter",
+        }
+    }
+
+    /// The colours from least to most active, starting with the colour of days without contributions
+    fn colours(&self) -> [Rgba; 5] {
+        match self {
+            Theme::Github => [
+                Rgba::new(235, 237, 240, 255), // #ebedf0ff
+                Rgba::new(155, 233, 168, 255), // #9be9a8ff
+                Rgba::new(64, 196, 99, 255),   // #40c463ff
+                Rgba::new(48, 161, 78, 255),   // #30a14eff
+                Rgba::new(33, 110, 57, 255),   // #216e39ff
+            ],
+            Theme::GithubDark => [
+                Rgba::new(22, 27, 34, 255),  // #161b22ff
+                Rgba::new(14, 68, 41, 255),  // #0e4429ff
+                Rgba::new(0, 109, 50, 255),  // #006d32ff
+                Rgba::new(38, 166, 65, 255), // #26a641ff
+                Rgba::new(57, 211, 83, 255), // #39d353ff
+            ],
+            Theme::Gitlab => [
+                Rgba::new(236, 236, 239, 255), // #ececefff
+                Rgba::new(210, 220, 255, 255), // #d2dcffff
+                Rgba::new(121, 146, 245, 255), // #7992f5ff
+                Rgba::new(78, 101, 205, 255),  // #4e65cdff
+                Rgba::new(48, 52, 112, 255),   // #303470ff
+            ],
+            // Based on viridis, which remains distinguishable with all common forms of colour blindness
+            Theme::ColourBlind => [
+                Rgba::new(235, 237, 240, 255), // #ebedf0ff
+                Rgba::new(253, 231, 37, 255),  // #fde725ff
+                Rgba::new(94, 201, 98, 255),   // #5ec962ff
+                Rgba::new(33, 145, 140, 255),  // #21918cff
+                Rgba::new(59, 82, 139, 255),   // #3b528bff
+            ],
+            Theme::HighContrast => [
+                Rgba::new(238, 238, 238, 255), // #eeeeeeff
+                Rgba::new(160, 160, 160, 255), // #a0a0a0ff
+                Rgba::new(100, 100, 100, 255), // #646464ff
+                Rgba::new(50, 50, 50, 255),    // #323232ff
+                Rgba::new(0, 0, 0, 255),       // #000000ff
+            ],
+            Theme::Halloween => [
+                Rgba::new(235, 237, 240, 255), // #ebedf0ff
+                Rgba::new(255, 238, 74, 255),  // #ffee4aff
+                Rgba::new(255, 197, 1, 255),   // #ffc501ff
+                Rgba::new(254, 150, 0, 255),   // #fe9600ff
+                Rgba::new(3, 0, 28, 255),      // #03001cff
+            ],
+            Theme::Winter => [
+                Rgba::new(235, 237, 240, 255), // #ebedf0ff
+                Rgba::new(182, 227, 255, 255), // #b6e3ffff
+                Rgba::new(84, 174, 255, 255),  // #54aeffff
+                Rgba::new(9, 105, 218, 255),   // #0969daff
+                Rgba::new(10, 48, 105, 255),   // #0a3069ff
+            ],
+        }
+    }
+
+    /// The minimum contribution count of each colour after the first one
+    fn thresholds(&self) -> [usize; 4] {
+        match self {
+            Theme::Gitlab => [1, 10, 20, 30],
+            _ => [1, 3, 6, 10],
+        }
+    }
+}
+
+impl From<Theme> for ColourStrategy {
+    fn from(theme: Theme) -> Self {
+        ColourStrategy::DiscreteStrategy {
+            colours: theme.colours().into(),
+            thresholds: theme.thresholds().into(),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownThemeError(pub String);
+
+impl FromStr for Theme {
+    type Err = UnknownThemeError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| UnknownThemeError(name.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Theme, UnknownThemeError};
+    use crate::svg::contribution_colour::{ColourStrategy, ContributionInfo};
+    use crate::svg::rgba::Rgba;
+
+    #[test]
+    fn parse() {
+        for theme in Theme::ALL {
+            assert_eq!(theme.name().parse(), Ok(theme));
+        }
+        assert_eq!("GitHub-Dark".parse(), Ok(Theme::GithubDark));
+        assert_eq!(
+            "solarized".parse::<Theme>(),
+            Err(UnknownThemeError("solarized".into()))
+        );
+    }
+
+    #[test]
+    fn gitlab_matches_gitlab_strategy() {
+        let theme = ColourStrategy::from(Theme::Gitlab);
+        for count_today in [0, 1, 9, 10, 19, 20, 29, 30, 500] {
+            let info = || ContributionInfo {
+                average_count_per_day: 3.0,
+                count_today,
+                active_counts: &[],
+            };
+            assert_eq!(
+                theme.get_colour(info()),
+                ColourStrategy::GitlabStrategy.get_colour(info())
+            );
+        }
+    }
+
+    #[test]
+    fn github() {
+        let theme = ColourStrategy::from(Theme::Github);
+        let colour = |count_today| {
+            theme.get_colour(ContributionInfo {
+                average_count_per_day: 0.0,
+                count_today,
+                active_counts: &[],
+            })
+        };
+        assert_eq!(colour(0), Rgba::new(235, 237, 240, 255));
+        assert_eq!(colour(2), Rgba::new(155, 233, 168, 255));
+        assert_eq!(colour(3), Rgba::new(64, 196, 99, 255));
+        assert_eq!(colour(100), Rgba::new(33, 110, 57, 255));
+    }
+}
@@ -6,6 +6,8 @@ use std::{
 
 use serde::{ser::SerializeMap, Serialize, Serializer};
 
+use super::DateRange;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct ContributionActivity(BTreeMap<NaiveDate, usize>);
 
@@ -38,6 +40,21 @@ impl ContributionActivity {
     pub fn contribution_count(&self) -> usize {
         self.0.iter().map(|(_, count)| count).sum()
     }
+
+    /// Iterate over all days with their contribution count in chronological order
+    pub fn iter(&self) -> impl Iterator<Item = (NaiveDate, usize)> + '_ {
+        self.0.iter().map(|(date, count)| (*date, *count))
+    }
+
+    /// Only keep the days within `range`
+    pub fn within(&self, range: &DateRange) -> Self {
+        Self(
+            self.0
+                .range(range.from()..=range.to())
+                .map(|(date, count)| (*date, *count))
+                .collect(),
+        )
+    }
 }
 
 impl From<BTreeMap<NaiveDate, usize>> for ContributionActivity {
@@ -72,6 +89,7 @@ impl AddAssign for ContributionActivity {
 #[cfg(test)]
 mod tests {
     use super::ContributionActivity;
+    use crate::types::DateRange;
     use chrono::NaiveDate;
     use std::collections::BTreeMap;
 
@@ -87,4 +105,18 @@ mod tests {
         let third = NaiveDate::from_ymd_opt(2024, 01, 03).unwrap();
         assert_eq!(activity.get(&third), None);
     }
+
+    #[test]
+    fn within() {
+        let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let second = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let third = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        let activity = ContributionActivity(BTreeMap::from([(first, 1), (second, 2), (third, 3)]));
+
+        let range = DateRange::new(second, third).unwrap();
+        assert_eq!(
+            activity.within(&range),
+            ContributionActivity(BTreeMap::from([(second, 2), (third, 3)]))
+        );
+    }
 }
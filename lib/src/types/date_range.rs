@@ -0,0 +1,255 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use super::{Error, Result, YEAR};
+use crate::provider::parse_date;
+
+/// The most years a range parsed from user input may span,
+/// as each year is fetched from the providers and every day is drawn
+const MAX_YEARS: u32 = 5;
+
+/// An inclusive range of days, such as the last year, a calendar year or a quarter.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct DateRange {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl DateRange {
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Result<Self> {
+        if from > to {
+            return Err(Error::InvalidDateRange(format!("{from} is after {to}")));
+        }
+
+        Ok(Self { from, to })
+    }
+
+    /// The year up to and including `to`, starting no earlier than the first representable day
+    pub fn year_until(to: NaiveDate) -> Self {
+        Self {
+            from: to.checked_sub_months(YEAR).unwrap_or(NaiveDate::MIN),
+            to,
+        }
+    }
+
+    /// The year up to and including today
+    pub fn last_year() -> Self {
+        Self::year_until(chrono::Local::now().date_naive())
+    }
+
+    /// The last `days` days up to and including today
+    pub fn last_days(days: u64) -> Self {
+        let to = chrono::Local::now().date_naive();
+        Self {
+            from: to
+                .checked_sub_days(Days::new(days.saturating_sub(1)))
+                .unwrap_or(NaiveDate::MIN),
+            to,
+        }
+    }
+
+    /// From the first of January to the 31st of December of `year`
+    pub fn calendar_year(year: i32) -> Result<Self> {
+        let invalid_year = || Error::InvalidDateRange(format!("Invalid year {year}"));
+        Self::new(
+            NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid_year)?,
+            NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(invalid_year)?,
+        )
+    }
+
+    /// From the first of January of `from` to the 31st of December of `to`.
+    /// If `to` is missing it defaults to the current year and if `from` is missing it defaults to `to`.
+    /// At most `MAX_YEARS` years are accepted.
+    pub fn calendar_years(from: Option<i32>, to: Option<i32>) -> Result<Self> {
+        let to = to.unwrap_or_else(|| chrono::Local::now().year());
+        let from = from.unwrap_or(to);
+        Self::new(Self::calendar_year(from)?.from, Self::calendar_year(to)?.to)?.limited()
+    }
+
+    /// Parse the bounds of a range formatted as `YYYY-MM-DD`.
+    /// If `to` is missing it defaults to today and if `from` is missing it defaults to one year before `to`.
+    /// Ranges spanning more than `MAX_YEARS` years are rejected.
+    pub fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self> {
+        let to = match to {
+            Some(to) => parse_date(to)?,
+            None => chrono::Local::now().date_naive(),
+        };
+
+        match from {
+            Some(from) => Self::new(parse_date(from)?, to)?.limited(),
+            None => Ok(Self::year_until(to)),
+        }
+    }
+
+    /// Reject ranges spanning more than `MAX_YEARS` years.
+    /// When the limit lies beyond the last representable day, `to` cannot reach it.
+    fn limited(self) -> Result<Self> {
+        match self.from.checked_add_months(Months::new(12 * MAX_YEARS)) {
+            Some(end) if end <= self.to => Err(Error::InvalidDateRange(format!(
+                "{} to {} spans more than {MAX_YEARS} years",
+                self.from, self.to
+            ))),
+            _ => Ok(self),
+        }
+    }
+
+    pub fn from(&self) -> NaiveDate {
+        self.from
+    }
+
+    pub fn to(&self) -> NaiveDate {
+        self.to
+    }
+
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        (self.from..=self.to).contains(date)
+    }
+
+    /// The days contained in both ranges, if there are any
+    pub fn intersection(&self, other: &DateRange) -> Option<DateRange> {
+        Self::new(self.from.max(other.from), self.to.min(other.to)).ok()
+    }
+
+    /// Split the range into consecutive ranges spanning one year at most,
+    /// which is the largest window some providers are able to serve at once.
+    pub fn years(&self) -> Vec<DateRange> {
+        let mut result = vec![];
+        let mut from = self.from;
+
+        loop {
+            let to = from
+                .checked_add_months(YEAR)
+                .and_then(|next_year| next_year.checked_sub_days(Days::new(1)))
+                .map_or(self.to, |end_of_year| end_of_year.min(self.to));
+            result.push(Self { from, to });
+
+            if to == self.to {
+                return result;
+            }
+
+            from = to + Days::new(1);
+        }
+    }
+}
+
+impl Default for DateRange {
+    fn default() -> Self {
+        Self::last_year()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateRange;
+    use crate::types::Error;
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn invalid_range() {
+        assert!(DateRange::new(date(2024, 1, 2), date(2024, 1, 1)).is_err());
+        assert!(DateRange::new(date(2024, 1, 1), date(2024, 1, 1)).is_ok());
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            DateRange::parse(Some("2023-01-01"), Some("2023-03-31")),
+            DateRange::new(date(2023, 1, 1), date(2023, 3, 31))
+        );
+        assert_eq!(
+            DateRange::parse(None, Some("2024-12-13")),
+            Ok(DateRange::year_until(date(2024, 12, 13)))
+        );
+        assert!(DateRange::parse(Some("yesterday"), None).is_err());
+    }
+
+    #[test]
+    fn parse_too_long() {
+        assert_eq!(
+            DateRange::parse(Some("2019-01-01"), Some("2023-12-31")),
+            DateRange::new(date(2019, 1, 1), date(2023, 12, 31))
+        );
+        assert_eq!(
+            DateRange::parse(Some("0001-01-01"), Some("9999-12-31")),
+            Err(Error::InvalidDateRange(
+                "0001-01-01 to 9999-12-31 spans more than 5 years".into()
+            ))
+        );
+        assert!(DateRange::parse(Some("2019-01-01"), Some("2024-01-01")).is_err());
+    }
+
+    #[test]
+    fn calendar_years() {
+        assert_eq!(
+            DateRange::calendar_years(Some(2021), Some(2023)),
+            DateRange::new(date(2021, 1, 1), date(2023, 12, 31))
+        );
+        assert_eq!(
+            DateRange::calendar_years(None, Some(2023)),
+            DateRange::calendar_year(2023)
+        );
+        assert!(DateRange::calendar_years(Some(2024), Some(2023)).is_err());
+        assert!(DateRange::calendar_years(Some(2019), Some(2023)).is_ok());
+        assert!(DateRange::calendar_years(Some(2018), Some(2023)).is_err());
+        assert_eq!(
+            DateRange::calendar_years(Some(1), Some(9999)),
+            Err(Error::InvalidDateRange(
+                "0001-01-01 to 9999-12-31 spans more than 5 years".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        let year = DateRange::calendar_year(2023).unwrap();
+        let range = DateRange::new(date(2022, 6, 1), date(2023, 3, 31)).unwrap();
+
+        assert_eq!(
+            year.intersection(&range),
+            DateRange::new(date(2023, 1, 1), date(2023, 3, 31)).ok()
+        );
+        assert_eq!(
+            year.intersection(&DateRange::calendar_year(2024).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn years() {
+        let range = DateRange::new(date(2022, 3, 1), date(2024, 1, 15)).unwrap();
+        assert_eq!(
+            range.years(),
+            vec![
+                DateRange::new(date(2022, 3, 1), date(2023, 2, 28)).unwrap(),
+                DateRange::new(date(2023, 3, 1), date(2024, 1, 15)).unwrap(),
+            ]
+        );
+
+        let range = DateRange::calendar_year(2023).unwrap();
+        assert_eq!(range.years(), vec![range]);
+    }
+
+    #[test]
+    fn bounds() {
+        let min = NaiveDate::MIN.to_string();
+        let max = NaiveDate::MAX.to_string();
+
+        assert_eq!(
+            DateRange::parse(None, Some(&min)),
+            DateRange::new(NaiveDate::MIN, NaiveDate::MIN)
+        );
+
+        let range = DateRange::parse(Some("+262142-12-01"), Some(&max)).unwrap();
+        assert_eq!(range.years(), vec![range]);
+
+        let year = DateRange::calendar_years(Some(262142), Some(262142)).unwrap();
+        assert_eq!(year.years(), vec![year]);
+
+        let range = DateRange::new(date(262140, 1, 1), NaiveDate::MAX).unwrap();
+        assert_eq!(range.years().len(), 3);
+        assert!(DateRange::parse(Some("+262137-12-31"), Some(&max)).is_err());
+    }
+}
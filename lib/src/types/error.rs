@@ -11,14 +11,23 @@ pub enum Error {
     TooltipMissing,
     UnexpectedTooltipMessage(String),
     UnableToParseDate(String),
+    InvalidDateRange(String),
+    /// The URL cannot have a path, such as `mailto:` URLs
+    InvalidUrl(String),
+    /// Parameters that cannot be served, such as too many compared users
+    InvalidQuery(String),
     UnableToParseJson(String),
     ReqwestError(String),
     GitError(String),
     UserNotFound,
     RepositoryCloningTimedOut,
+    CassetteError(String),
 
     #[cfg(feature = "svg")]
     BuilderError(BuilderError),
+
+    #[cfg(feature = "png")]
+    RasterError(String),
 }
 
 #[cfg(feature = "svg")]
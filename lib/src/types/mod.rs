@@ -1,7 +1,9 @@
 mod contribution_activity;
+mod date_range;
 mod error;
 
 pub use contribution_activity::ContributionActivity;
+pub use date_range::DateRange;
 pub use error::Error;
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,200 @@
+use crate::Contributions;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
+use tokio::{net::TcpListener, task::JoinHandle};
+use url::Url;
+
+type Users = Arc<BTreeMap<String, Contributions>>;
+
+/// A local HTTP server emulating the endpoints of GitHub, GitLab and Gitea
+/// which are used to extract contribution activity.
+/// The server is shut down when dropped.
+pub struct MockForge {
+    address: SocketAddr,
+    /// The path the endpoints are served below, such as `/` or `/github/`
+    base_path: String,
+    server: JoinHandle<()>,
+}
+
+impl MockForge {
+    /// Start serving the contributions of the given users on a random local port.
+    /// Every other user is reported as not found.
+    pub async fn start(users: BTreeMap<String, Contributions>) -> Self {
+        Self::start_below("", users).await
+    }
+
+    /// Like `start`, but serving the endpoints only below `/{prefix}/`,
+    /// like a forge mounted behind a proxy
+    pub async fn start_below(prefix: &str, users: BTreeMap<String, Contributions>) -> Self {
+        let routes = Router::new()
+            .route("/", get(home))
+            .route("/users/{user}/contributions", get(github_contributions))
+            .route("/users/{user}/calendar.json", get(gitlab_calendar))
+            .route("/{user}", get(gitea_activity));
+        let (router, base_path) = match prefix {
+            "" => (routes, "/".to_owned()),
+            prefix => (
+                Router::new().nest(&format!("/{prefix}"), routes),
+                format!("/{prefix}/"),
+            ),
+        };
+        let router = router.with_state(Arc::new(users));
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Unable to bind mock forge");
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Self {
+            address,
+            base_path,
+            server,
+        }
+    }
+
+    /// Start serving the contributions of a single user
+    pub async fn with_user(user: &str, contributions: Contributions) -> Self {
+        Self::start(BTreeMap::from([(user.to_owned(), contributions)])).await
+    }
+
+    /// The base URL of the forge, e.g. `http://127.0.0.1:12345/`
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}{}", self.address, self.base_path)).unwrap()
+    }
+}
+
+impl Drop for MockForge {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn html(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], body).into_response()
+}
+
+fn lookup(users: &Users, user: &str) -> Result<Contributions, StatusCode> {
+    users.get(user).cloned().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// The landing page, which Gitea serves when the user name is empty
+async fn home() -> Response {
+    html(
+        "<!doctype html>\n<html><body><div class=\"page-content home\"></div></body></html>".into(),
+    )
+}
+
+/// `GET /users/{user}/contributions?from=YYYY-MM-DD&to=YYYY-MM-DD` as served by GitHub
+async fn github_contributions(
+    State(users): State<Users>,
+    Path(user): Path<String>,
+    Query(query): Query<BTreeMap<String, String>>,
+) -> Result<Response, StatusCode> {
+    let bound = |name: &str| {
+        query
+            .get(name)
+            .map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|_| StatusCode::BAD_REQUEST)
+    };
+    let (from, to) = (bound("from")?, bound("to")?);
+
+    let contributions = lookup(&users, &user)?
+        .into_iter()
+        .filter(|(date, _)| {
+            from.is_none_or(|from| *date >= from) && to.is_none_or(|to| *date <= to)
+        })
+        .collect::<Contributions>();
+
+    let (cells, tool_tips): (Vec<_>, Vec<_>) = contributions
+        .iter()
+        .enumerate()
+        .map(|(index, (date, count))| {
+            let id = format!(
+                "contribution-day-component-{}-{index}",
+                date.weekday().num_days_from_sunday()
+            );
+            let text = match count {
+                0 => "No contributions".to_owned(),
+                1 => "1 contribution".to_owned(),
+                c => format!("{c} contributions"),
+            };
+
+            (
+                format!(
+                    r#"<td data-date="{date}" id="{id}" class="ContributionCalendar-day"></td>"#
+                ),
+                format!(
+                    r#"<tool-tip for="{id}">{text} on {}.</tool-tip>"#,
+                    date.format("%B %-d")
+                ),
+            )
+        })
+        .unzip();
+
+    Ok(html(format!(
+        r#"<div class="js-yearly-contributions">
+<div><table><tbody><tr>
+{}
+</tr></tbody></table></div>
+{}
+</div>"#,
+        cells.join("\n"),
+        tool_tips.join("\n"),
+    )))
+}
+
+/// `GET /users/{user}/calendar.json` as served by GitLab
+async fn gitlab_calendar(
+    State(users): State<Users>,
+    Path(user): Path<String>,
+) -> Result<Response, StatusCode> {
+    let calendar = lookup(&users, &user)?
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(date, count)| (date.to_string(), count))
+        .collect::<BTreeMap<_, _>>();
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        serde_json::to_string(&calendar).unwrap(),
+    )
+        .into_response())
+}
+
+/// `GET /{user}?tab=activity` as served by Gitea
+async fn gitea_activity(
+    State(users): State<Users>,
+    Path(user): Path<String>,
+) -> Result<Response, StatusCode> {
+    let heatmap = lookup(&users, &user)?
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(date, count)| {
+            let timestamp = date.and_time(NaiveTime::MIN).and_utc().timestamp();
+            serde_json::json!({ "timestamp": timestamp, "contributions": count })
+        })
+        .collect::<Vec<_>>();
+    let heatmap = serde_json::to_string(&heatmap)
+        .unwrap()
+        .replace('"', "&#34;");
+
+    Ok(html(format!(
+        r#"<!doctype html>
+<html lang="en">
+    <body>
+        <div id="user-heatmap" class="is-loading" data-heatmap-data="{heatmap}"></div>
+    </body>
+</html>"#
+    )))
+}
@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, NaiveTime};
+use git2::{Signature, Time};
+use std::path::PathBuf;
+use url::Url;
+use uuid::Uuid;
+
+/// A local Git repository reachable by a `file://` URL.
+/// The repository is removed from disk when dropped.
+pub struct GitRepository {
+    path: PathBuf,
+    repository: git2::Repository,
+}
+
+impl GitRepository {
+    /// Create an empty repository in a temporary directory
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("commitoria-{}", Uuid::new_v4()));
+        let repository = git2::Repository::init(&path).expect("Unable to create repository");
+        Self { path, repository }
+    }
+
+    /// Commit an empty change on top of `HEAD`, authored by `name` and `email` on `date`
+    pub fn commit(&self, name: &str, email: &str, date: NaiveDate) -> &Self {
+        let seconds = date.and_time(NaiveTime::MIN).and_utc().timestamp();
+        let signature = Signature::new(name, email, &Time::new(seconds, 0)).unwrap();
+
+        let tree = self.repository.treebuilder(None).unwrap().write().unwrap();
+        let tree = self.repository.find_tree(tree).unwrap();
+        let parent = self
+            .repository
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap());
+
+        self.repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!("Commit on {date}"),
+                &tree,
+                &parent.iter().collect::<Vec<_>>(),
+            )
+            .unwrap();
+        self
+    }
+
+    /// The `file://` URL of the repository, suitable for cloning
+    pub fn url(&self) -> Url {
+        Url::from_directory_path(&self.path).unwrap()
+    }
+}
+
+impl Default for GitRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for GitRepository {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            eprintln!("Failed to remove directory when repository was dropped: {e}");
+        }
+    }
+}
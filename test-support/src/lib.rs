@@ -0,0 +1,14 @@
+//! Test utilities emulating the sources Commitoria aggregates from,
+//! so that the library and the web server can be tested without network access.
+
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+pub mod forge;
+pub mod git;
+
+pub use forge::MockForge;
+pub use git::GitRepository;
+
+/// Contribution count per day, as served by the emulated sources
+pub type Contributions = BTreeMap<NaiveDate, usize>;
@@ -1,19 +1,25 @@
 use crate::query::CalendarQuery;
 use axum::{
-    http::{HeaderMap, HeaderValue, StatusCode},
+    http::{header::ACCEPT_LANGUAGE, HeaderMap, HeaderValue, StatusCode},
     response::IntoResponse,
     routing::get,
-    Router,
+    Json, Router,
 };
 use axum_extra::extract::Query;
 use commitoria_lib::{
     provider::{git::Repository, gitea::Gitea, github::Github, gitlab::Gitlab, RepositoryKind},
     source::ReqwestDataSource,
-    svg::svg_renderer::SvgRenderer,
-    types::{ContributionActivity, Error},
+    statistics::Statistics,
+    svg::{
+        isometric_renderer::IsometricRenderer,
+        locale::Locale,
+        raster::{rasterise, RasterFormat},
+        renderer::Renderer,
+    },
+    types::{ContributionActivity, DateRange, Error},
 };
 use const_format::concatcp;
-use query::{ParsedQuery, Repositories};
+use query::{Layout, ParsedQuery, Repositories, Style};
 use std::{net::SocketAddr, sync::Arc};
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 
@@ -33,56 +39,171 @@ macro_rules! static_file {
     }};
 }
 
-fn get_svg_headers() -> HeaderMap {
+fn get_headers(content_type: &'static str) -> HeaderMap {
     let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", HeaderValue::from_static("image/svg+xml"));
+    headers.insert("Content-Type", HeaderValue::from_static(content_type));
     headers.insert(
         "Cache-Control",
         HeaderValue::from_static(concatcp!("max-age=", MAX_SVG_CACHE_AGE_IN_SECONDS)),
     );
+    headers.insert("Vary", HeaderValue::from_static("Accept-Language"));
     headers
 }
 
-async fn get_calendar_data(repositories: Repositories) -> Result<ContributionActivity, Error> {
-    let mut activity = ContributionActivity::new();
+/// Use the preferred language of the client unless the query selects a locale
+fn negotiate_locale(query: &mut CalendarQuery, headers: &HeaderMap) {
+    if query.locale.is_none() {
+        query.locale = headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Locale::negotiate)
+            .map(|locale| locale.code().to_owned());
+    }
+}
+
+async fn get_calendar_data(
+    repositories: Repositories,
+    range: &DateRange,
+) -> Result<ContributionActivity, Error> {
+    let sources = get_source_data(repositories, range).await?;
+    Ok(sources
+        .into_iter()
+        .fold(ContributionActivity::new(), |total, (_, activity)| {
+            total + activity
+        }))
+}
+
+/// The activity of every source, named after the host of its repositories
+async fn get_source_data(
+    repositories: Repositories,
+    range: &DateRange,
+) -> Result<Vec<(String, ContributionActivity)>, Error> {
+    let mut sources: Vec<(String, ContributionActivity)> = vec![];
+    let mut add = |name: String, activity| match sources.iter_mut().find(|(n, _)| *n == name) {
+        Some((_, total)) => *total += activity,
+        None => sources.push((name, activity)),
+    };
 
     if let Some(name) = &repositories.github {
-        activity += Github::fetch(ReqwestDataSource {}, name.clone()).await?;
+        add(
+            "github.com".into(),
+            Github::fetch_range(ReqwestDataSource {}, name.clone(), range).await?,
+        );
     }
 
     for repository in repositories.repositories {
-        activity += match repository.kind {
+        let (user_name, url) = (repository.user_name, repository.url);
+        let name = match (url.host_str(), url.port()) {
+            (Some(host), None) => host.to_owned(),
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (None, _) => url.path().to_owned(),
+        };
+        let activity = match repository.kind {
             RepositoryKind::BareGitRepository => {
-                Repository::new(repository.url)
+                Repository::new(url)
                     .await?
-                    .get_activity(repository.user_name)
+                    .get_activity_in(user_name, range)
                     .await?
             }
             RepositoryKind::Gitea => {
-                Gitea::fetch(ReqwestDataSource {}, repository.user_name, repository.url).await?
+                Gitea::fetch_range(ReqwestDataSource {}, user_name, url, range).await?
             }
             RepositoryKind::Gitlab => {
-                Gitlab::fetch(ReqwestDataSource {}, repository.user_name, repository.url).await?
+                Gitlab::fetch_range(ReqwestDataSource {}, user_name, url, range).await?
             }
         };
+        add(name, activity);
     }
 
-    Ok(activity)
+    Ok(sources)
+}
+
+async fn render_svg(query: CalendarQuery) -> Result<String, Error> {
+    let split_sources = query.split_sources.unwrap_or(false);
+    let ParsedQuery(repositories, range, layout, style, builder) = query.try_into()?;
+    let renderer = builder.build()?;
+    let layout = match (layout, split_sources) {
+        (Layout::Calendar, false) => {
+            renderer.layout(&get_calendar_data(repositories, &range).await?, &range)
+        }
+        (Layout::Years, false) => {
+            renderer.layout_years(&get_calendar_data(repositories, &range).await?, &range)
+        }
+        (Layout::Calendar, true) => {
+            renderer.layout_sources(&get_source_data(repositories, &range).await?, &range)
+        }
+        (Layout::Years, true) => {
+            renderer.layout_years_sources(&get_source_data(repositories, &range).await?, &range)
+        }
+    };
+    Ok(match style {
+        Style::Calendar => renderer.render_layout(&layout),
+        Style::Skyline => IsometricRenderer::default().render_layout(&layout),
+    })
+}
+
+async fn get_comparison_svg(
+    headers: HeaderMap,
+    Query(mut query): Query<CalendarQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    negotiate_locale(&mut query, &headers);
+    let users = query.parse_users()?;
+    if query.split_sources.unwrap_or(false) {
+        return Err(
+            Error::InvalidQuery("split_sources is not supported for comparisons".into()).into(),
+        );
+    }
+    let ParsedQuery(_, range, layout, style, builder) = query.try_into()?;
+    if let Layout::Years = layout {
+        return Err(Error::InvalidQuery(
+            "from_year and to_year are not supported for comparisons".into(),
+        )
+        .into());
+    }
+    let renderer = builder.build().map_err(Error::from)?;
+
+    let mut rows = vec![];
+    for (name, repositories) in users {
+        rows.push((name, get_calendar_data(repositories, &range).await?));
+    }
+    let layout = renderer.layout_comparison(&rows, &range);
+    let svg = match style {
+        Style::Calendar => renderer.render_layout(&layout),
+        Style::Skyline => IsometricRenderer::default().render_layout(&layout),
+    };
+    Ok((get_headers("image/svg+xml"), svg))
 }
 
 async fn get_calendar_svg(
-    Query(query): Query<CalendarQuery>,
+    headers: HeaderMap,
+    Query(mut query): Query<CalendarQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let ParsedQuery(repositories, builder) = query.try_into()?;
-    let activity = get_calendar_data(repositories).await?;
-    let result: Result<SvgRenderer, Error> = builder.build().map_err(|e| e.into());
-    Ok((get_svg_headers(), result?.render(&activity)))
+    negotiate_locale(&mut query, &headers);
+    let svg = render_svg(query).await?;
+    Ok((get_headers("image/svg+xml"), svg))
 }
 
-#[tokio::main]
-async fn main() {
+async fn get_calendar_png(
+    headers: HeaderMap,
+    Query(mut query): Query<CalendarQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    negotiate_locale(&mut query, &headers);
+    let pixel_ratio = query.pixel_ratio.unwrap_or(1.0);
+    let svg = render_svg(query).await?;
+    let format = RasterFormat::Png;
+    // Rendering is CPU-bound, so it must not block the async runtime
+    let png = tokio::task::spawn_blocking(move || rasterise(&svg, pixel_ratio, format))
+        .await
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))??;
+    Ok((get_headers(format.content_type()), png))
+}
+
+fn app() -> Router {
     let rate_limited_routes = Router::new()
         .route("/api/calendar.svg", get(get_calendar_svg))
+        .route("/api/calendar.png", get(get_calendar_png))
+        .route("/api/comparison.svg", get(get_comparison_svg))
+        .route("/api/statistics.json", get(get_statistics))
         .layer(GovernorLayer {
             config: Arc::new(
                 GovernorConfigBuilder::default()
@@ -112,15 +233,405 @@ async fn main() {
             static_file!("gitlab-calendar/main.css", "text/css"),
         );
 
-    let app = Router::new()
+    Router::new()
         .merge(rate_limited_routes)
-        .merge(static_routes);
+        .merge(static_routes)
+}
 
+async fn get_statistics(
+    Query(query): Query<CalendarQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let ParsedQuery(repositories, range, ..) = query.try_into()?;
+    let activity = get_calendar_data(repositories, &range).await?;
+    Ok(Json(Statistics::new(&activity)))
+}
+
+#[tokio::main]
+async fn main() {
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
     axum::serve(
         listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
+        app().into_make_service_with_connect_info::<SocketAddr>(),
     )
     .await
     .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::app;
+    use chrono::{Days, Local};
+    use commitoria_lib::source::{DataSource, ReqwestDataSource};
+    use commitoria_test_support::{Contributions, GitRepository, MockForge};
+    use std::net::SocketAddr;
+    use url::Url;
+
+    async fn serve() -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app().into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+        Url::parse(&format!("http://{address}/")).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg() {
+        let day = Local::now().date_naive() - Days::new(7);
+        let forge = MockForge::with_user("alice", Contributions::from([(day, 1)])).await;
+        let origin = GitRepository::new();
+        origin.commit("alice", "alice@example.com", day);
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        for (kind, repository_url) in [
+            ("Gitlab", forge.url()),
+            ("Gitea", forge.url()),
+            ("BareGitRepository", origin.url()),
+        ] {
+            let repository = serde_json::json!({
+                "url": repository_url,
+                "user_name": "alice",
+                "kind": kind,
+            });
+            url.query_pairs_mut()
+                .append_pair("repositories", &repository.to_string());
+        }
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(&format!(
+            r#"data-hover-info="3 contributions" data-date="{day}""#
+        )));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_sources() {
+        let day = Local::now().date_naive() - Days::new(7);
+        let forge = MockForge::with_user("alice", Contributions::from([(day, 3)])).await;
+        let origin = GitRepository::new();
+        origin.commit("alice", "alice@example.com", day);
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        for (kind, repository_url) in [("Gitlab", forge.url()), ("BareGitRepository", origin.url())]
+        {
+            let repository = serde_json::json!({
+                "url": repository_url,
+                "user_name": "alice",
+                "kind": kind,
+            });
+            url.query_pairs_mut()
+                .append_pair("repositories", &repository.to_string());
+        }
+        url.query_pairs_mut()
+            .append_pair("split_sources", "true")
+            .append_pair("source_colours", "#ff0000")
+            .append_pair("source_colours", "#0000ff")
+            .append_pair("legend", "true");
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(&format!(
+            r##"<linearGradient id="user-contrib-sources-{day}" x2="0" y2="1"><stop offset="0.000" stop-color="#ff0000ff"></stop><stop offset="0.750" stop-color="#ff0000ff"></stop><stop offset="0.750" stop-color="#0000ffff"></stop>"##
+        )));
+        assert!(svg.contains(&format!(r#"fill="url(#user-contrib-sources-{day})""#)));
+        assert!(svg.contains(r#"data-testid="user-contrib-source-legend""#));
+        assert!(svg.contains(&format!(">{}</text>", forge.url().authority())));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn comparison_svg() {
+        let day = Local::now().date_naive() - Days::new(7);
+        let forge = MockForge::with_user("alice", Contributions::from([(day, 2)])).await;
+        let origin = GitRepository::new();
+        origin.commit("bob", "bob@example.com", day);
+
+        let mut url = serve().await.join("api/comparison.svg").unwrap();
+        for (name, kind, repository_url) in [
+            ("Alice", "Gitlab", forge.url()),
+            ("Bob", "BareGitRepository", origin.url()),
+        ] {
+            let user = serde_json::json!({
+                "name": name,
+                "repositories": [{
+                    "url": repository_url,
+                    "user_name": name.to_lowercase(),
+                    "kind": kind,
+                }],
+            });
+            url.query_pairs_mut()
+                .append_pair("users", &user.to_string());
+        }
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert_eq!(svg.matches(r#"data-testid="user-contrib-year""#).count(), 2);
+        assert!(svg.contains(">Alice: 2 contributions</text>"));
+        assert!(svg.contains(">Bob: 1 contribution</text>"));
+
+        let mut url = serve().await.join("api/comparison.svg").unwrap();
+        url.query_pairs_mut().append_pair("users", "{}");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+
+        let mut url = serve().await.join("api/comparison.svg").unwrap();
+        for i in 0..9 {
+            let user = serde_json::json!({ "name": format!("User {i}") });
+            url.query_pairs_mut()
+                .append_pair("users", &user.to_string());
+        }
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+
+        for (name, value) in [("split_sources", "true"), ("from_year", "2023")] {
+            let mut url = serve().await.join("api/comparison.svg").unwrap();
+            url.query_pairs_mut()
+                .append_pair("users", r#"{"name": "Alice"}"#)
+                .append_pair(name, value);
+            assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn statistics() {
+        let day = Local::now().date_naive() - Days::new(7);
+        let forge = MockForge::with_user(
+            "alice",
+            Contributions::from([(day - Days::new(1), 2), (day, 1)]),
+        )
+        .await;
+
+        let mut url = serve().await.join("api/statistics.json").unwrap();
+        let repository = serde_json::json!({
+            "url": forge.url(),
+            "user_name": "alice",
+            "kind": "Gitlab",
+        });
+        url.query_pairs_mut()
+            .append_pair("repositories", &repository.to_string());
+
+        let json = ReqwestDataSource {}.fetch(url).await.unwrap();
+        let statistics: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(statistics["contribution_count"], 3);
+        assert_eq!(statistics["longest_streak"]["days"], 2);
+        assert_eq!(statistics["busiest_day"]["count"], 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_range() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2023-01-01")
+            .append_pair("to", "2023-03-31");
+
+        let svg = ReqwestDataSource {}.fetch(url.clone()).await.unwrap();
+        assert_eq!(svg.matches("<rect").count(), 90);
+
+        url.query_pairs_mut().append_pair("from", "2023-04-01");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_first_day_of_week() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2024-12-01")
+            .append_pair("to", "2024-12-01")
+            .append_pair("first_day_of_week", "Sun");
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(r#"<rect x="0" y="0""#));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_decorations() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("weekday_labels", "true")
+            .append_pair("legend", "true")
+            .append_pair("caption", "false");
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(r#"data-testid="user-contrib-weekdays""#));
+        assert!(svg.contains(r#"data-testid="user-contrib-legend""#));
+        assert!(!svg.contains(r#"data-testid="user-contrib-caption""#));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_dark_mode() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("dark_inactive_colour", "#161b22")
+            .append_pair("dark_active_colour", "#39d353");
+
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains("@media (prefers-color-scheme: dark)"));
+        assert!(svg.contains(r#"style="--dark-fill: #161b22ff""#));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_theme() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut().append_pair("theme", "winter");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(r##"fill="#ebedf0ff""##));
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut().append_pair("theme", "solarized");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_thresholds() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("colours", "#000000")
+            .append_pair("colours", "#ffffff")
+            .append_pair("thresholds", "1");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(r##"fill="#000000ff""##));
+        assert!(!svg.contains(r##"fill="#ececefff""##));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_colour_space() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("colour_strategy", "InterpolationStrategy")
+            .append_pair("colours", "#ffff00")
+            .append_pair("colours", "#800080")
+            .append_pair("colour_space", "oklch")
+            .append_pair("scale", "logarithmic");
+        assert!(ReqwestDataSource {}.fetch(url.clone()).await.is_ok());
+
+        url.query_pairs_mut().append_pair("colour_space", "oklch");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_css_colours() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("font_colour", "rebeccapurple")
+            .append_pair("colour_strategy", "InterpolationStrategy")
+            .append_pair("inactive_colour", "hsl(0 0% 100%)")
+            .append_pair("active_colour", "#080");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains("fill: #663399ff;"));
+        assert!(svg.contains(r##"fill="#ffffffff""##));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_png() {
+        let mut url = serve().await.join("api/calendar.png").unwrap();
+        url.query_pairs_mut().append_pair("pixel_ratio", "2");
+
+        let response = reqwest::get(url).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["Content-Type"], "image/png");
+        let png = response.bytes().await.unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut url = serve().await.join("api/calendar.png").unwrap();
+        url.query_pairs_mut().append_pair("pixel_ratio", "0");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_locale() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2023-01-01")
+            .append_pair("to", "2023-03-31")
+            .append_pair("locale", "de");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(">Mär</text>"));
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2023-01-01")
+            .append_pair("to", "2023-03-31");
+        let response = reqwest::Client::new()
+            .get(url)
+            .header("Accept-Language", "tlh, he;q=0.8, en;q=0.5")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["Vary"], "Accept-Language");
+        let svg = response.text().await.unwrap();
+        assert!(svg.contains(">מרץ</text>"));
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut().append_pair("locale", "tlh");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_skyline() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2023-01-01")
+            .append_pair("to", "2023-03-31")
+            .append_pair("style", "skyline");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(r#"data-testid="contrib-skyline""#));
+        assert_eq!(svg.matches(r#"class="skyline-column""#).count(), 90);
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut().append_pair("style", "pie");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_annotations() {
+        let today = Local::now().date_naive();
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("mark_today", "true")
+            .append_pair("date_labels", &format!("{today}:Release"))
+            .append_pair(
+                "holidays",
+                &format!(
+                    "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:{}\r\nSUMMARY:Leave\r\nEND:VEVENT\r\n",
+                    today.format("%Y%m%d")
+                ),
+            );
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert!(svg.contains(&format!(
+            r#"data-testid="user-contrib-today"><title>{today}</title>"#
+        )));
+        assert!(svg.contains(r#"data-testid="user-contrib-label"><title>Release</title>"#));
+        assert!(svg.contains(r#"data-testid="user-contrib-holiday"><title>Leave</title>"#));
+
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut().append_pair("date_labels", "Release");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_animation() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from", "2023-01-01")
+            .append_pair("to", "2023-01-31")
+            .append_pair("animation", "fill");
+        let svg = ReqwestDataSource {}.fetch(url).await.unwrap();
+        assert_eq!(svg.matches("user-contrib-fill\"").count(), 31);
+        assert!(svg.contains("@media (prefers-reduced-motion: no-preference)"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calendar_svg_years() {
+        let mut url = serve().await.join("api/calendar.svg").unwrap();
+        url.query_pairs_mut()
+            .append_pair("from_year", "2021")
+            .append_pair("to_year", "2023");
+
+        let svg = ReqwestDataSource {}.fetch(url.clone()).await.unwrap();
+        assert_eq!(svg.matches(r#"data-testid="user-contrib-year""#).count(), 3);
+        assert!(svg.contains(">2021: no contributions<"));
+
+        url.query_pairs_mut().append_pair("from", "2023-01-01");
+        assert!(ReqwestDataSource {}.fetch(url).await.is_err());
+    }
+}
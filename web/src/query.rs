@@ -1,16 +1,84 @@
-use commitoria_lib::{provider::RepositoryInfo, svg::svg_renderer};
+use commitoria_lib::{provider::RepositoryInfo, svg::svg_renderer, types::DateRange};
 use serde::Deserialize;
 
+/// The most users compared at once, as the activity of each is fetched separately
+const MAX_USERS: usize = 8;
+
 #[derive(Deserialize, Clone)]
 pub(crate) struct CalendarQuery {
     pub(crate) github: Option<String>,
+    /// Scale factor of raster images for high density displays
+    pub(crate) pixel_ratio: Option<f32>,
+    #[serde(default)]
+    style: Style,
     font_size: Option<usize>,
     cell_size: Option<usize>,
+    cell_shape: Option<String>,
+    cell_radius: Option<usize>,
+    cell_gap: Option<usize>,
+    margin: Option<usize>,
     colour_strategy: Option<String>,
+    theme: Option<String>,
     active_colour: Option<String>,
     inactive_colour: Option<String>,
+    colours: Option<Vec<String>>,
+    thresholds: Option<Vec<usize>>,
+    colour_space: Option<String>,
+    scale: Option<String>,
     repositories: Option<Vec<String>>,
+    /// JSON encoded `UserGroup`s compared side by side
+    users: Option<Vec<String>>,
     font_colour: Option<String>,
+    first_day_of_week: Option<String>,
+    weekday_labels: Option<bool>,
+    legend: Option<bool>,
+    caption: Option<bool>,
+    /// Split the cells between the platforms the contributions come from
+    pub(crate) split_sources: Option<bool>,
+    source_colours: Option<Vec<String>>,
+    source_split: Option<String>,
+    longest_streak: Option<bool>,
+    /// Mark the current date of the server
+    mark_today: Option<bool>,
+    /// Holidays or leave to shade, as the content of an iCalendar (`.ics`) file
+    holidays: Option<String>,
+    /// Texts attached to dates, formatted as `YYYY-MM-DD:text`
+    date_labels: Option<Vec<String>>,
+    animation: Option<String>,
+    /// Language of the labels, falling back to the `Accept-Language` header
+    pub(crate) locale: Option<String>,
+    dark_colour_strategy: Option<String>,
+    dark_theme: Option<String>,
+    dark_active_colour: Option<String>,
+    dark_inactive_colour: Option<String>,
+    dark_colours: Option<Vec<String>>,
+    dark_thresholds: Option<Vec<usize>>,
+    dark_colour_space: Option<String>,
+    dark_scale: Option<String>,
+    dark_font_colour: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    from_year: Option<i32>,
+    to_year: Option<i32>,
+}
+
+/// How the activity is laid out
+pub(crate) enum Layout {
+    /// A single calendar covering the whole range
+    Calendar,
+    /// One calendar per year, stacked vertically
+    Years,
+}
+
+/// How each day is drawn
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Style {
+    /// A grid of coloured cells
+    #[default]
+    Calendar,
+    /// Isometric columns whose height is proportional to the count
+    Skyline,
 }
 
 pub(crate) struct Repositories {
@@ -18,7 +86,22 @@ pub(crate) struct Repositories {
     pub(crate) repositories: Vec<RepositoryInfo>,
 }
 
-pub(crate) struct ParsedQuery(pub(crate) Repositories, pub(crate) svg_renderer::Builder);
+/// The repositories of one of the compared users, shown with `name`
+#[derive(Deserialize)]
+struct UserGroup {
+    name: String,
+    github: Option<String>,
+    #[serde(default)]
+    repositories: Vec<RepositoryInfo>,
+}
+
+pub(crate) struct ParsedQuery(
+    pub(crate) Repositories,
+    pub(crate) DateRange,
+    pub(crate) Layout,
+    pub(crate) Style,
+    pub(crate) svg_renderer::Builder,
+);
 
 impl TryFrom<CalendarQuery> for ParsedQuery {
     type Error = crate::Error;
@@ -33,6 +116,8 @@ impl TryFrom<CalendarQuery> for ParsedQuery {
             .collect::<serde_json::Result<Vec<RepositoryInfo>>>()?;
 
         let github = value.github.clone();
+        let (range, layout) = value.parse_range()?;
+        let style = value.style;
         let builder = value.into();
 
         Ok(Self(
@@ -40,22 +125,98 @@ impl TryFrom<CalendarQuery> for ParsedQuery {
                 github,
                 repositories,
             },
+            range,
+            layout,
+            style,
             builder,
         ))
     }
 }
 
-impl CalendarQuery {}
+impl CalendarQuery {
+    /// The named repositories of every compared user
+    pub(crate) fn parse_users(&self) -> Result<Vec<(String, Repositories)>, crate::Error> {
+        let users = self.users.as_deref().unwrap_or_default();
+        if users.len() > MAX_USERS {
+            return Err(crate::Error::InvalidQuery(format!(
+                "At most {MAX_USERS} users can be compared"
+            )));
+        }
+
+        users
+            .iter()
+            .map(|user| {
+                let user: UserGroup = serde_json::from_str(user)?;
+                Ok((
+                    user.name,
+                    Repositories {
+                        github: user.github,
+                        repositories: user.repositories,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn parse_range(&self) -> Result<(DateRange, Layout), crate::Error> {
+        match (self.from_year, self.to_year) {
+            (None, None) => Ok((
+                DateRange::parse(self.from.as_deref(), self.to.as_deref())?,
+                Layout::Calendar,
+            )),
+            _ if self.from.is_some() || self.to.is_some() => Err(crate::Error::InvalidDateRange(
+                "from_year and to_year cannot be combined with from and to".into(),
+            )),
+            (from_year, to_year) => Ok((
+                DateRange::calendar_years(from_year, to_year)?,
+                Layout::Years,
+            )),
+        }
+    }
+}
 
 impl From<CalendarQuery> for svg_renderer::Builder {
     fn from(query: CalendarQuery) -> Self {
         svg_renderer::Builder {
             cell_size: query.cell_size,
+            cell_shape: query.cell_shape,
+            cell_radius: query.cell_radius,
+            cell_gap: query.cell_gap,
+            margin: query.margin,
             colour_strategy: query.colour_strategy,
+            theme: query.theme,
             font_size: query.font_size,
             active_colour: query.active_colour,
             inactive_colour: query.inactive_colour,
-            font_colour: query.font_colour
+            colours: query.colours,
+            thresholds: query.thresholds,
+            colour_space: query.colour_space,
+            scale: query.scale,
+            font_colour: query.font_colour,
+            first_day_of_week: query.first_day_of_week,
+            weekday_labels: query.weekday_labels,
+            legend: query.legend,
+            caption: query.caption,
+            locale: query.locale,
+            source_colours: query.source_colours,
+            source_split: query.source_split,
+            longest_streak: query.longest_streak,
+            today: query
+                .mark_today
+                .unwrap_or(false)
+                .then(|| chrono::Local::now().date_naive()),
+            holidays: query.holidays,
+            date_labels: query.date_labels,
+            animation: query.animation,
+            dark_colour_strategy: query.dark_colour_strategy,
+            dark_theme: query.dark_theme,
+            dark_active_colour: query.dark_active_colour,
+            dark_inactive_colour: query.dark_inactive_colour,
+            dark_colours: query.dark_colours,
+            dark_thresholds: query.dark_thresholds,
+            dark_colour_space: query.dark_colour_space,
+            dark_scale: query.dark_scale,
+            dark_font_colour: query.dark_font_colour,
         }
     }
 }
@@ -28,6 +28,8 @@ async function fetchData(url) {
   const calendar = document.querySelector(".calendar");
   calendar.style.display = "inline-block";
   calendar.innerHTML = svg;
+  // the native tooltips would show up next to our popup
+  calendar.querySelectorAll(".user-contrib-cell > title").forEach((title) => title.remove());
   calendar.scrollLeft = calendar.scrollWidth;
 }
 
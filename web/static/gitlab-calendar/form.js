@@ -4,22 +4,69 @@ function colourStrategyChange(e) {
   const colourStrategy = document.querySelector("select#colour_strategy");
   const inactiveColour = document.querySelector("#inactive_colour");
   const activeColour = document.querySelector("#active_colour");
+  const colourSpace = document.querySelector("#colour_space");
+  const scale = document.querySelector("#scale");
+
+  const theme = document.querySelector("#theme");
+  const colours = document.querySelector("#colours");
 
   const inactiveColourInput = inactiveColour.querySelector("input");
   const activeColourInput = activeColour.querySelector("input");
+  const colourSpaceSelect = colourSpace.querySelector("select");
+  const scaleSelect = scale.querySelector("select");
+  const themeSelect = theme.querySelector("select");
 
   if (colourStrategy.value === "InterpolationStrategy") {
     inactiveColour.classList.remove('hidden');
     activeColour.classList.remove('hidden');
+    colourSpace.classList.remove('hidden');
+    scale.classList.remove('hidden');
 
     inactiveColourInput.setAttribute("name", "inactive_colour");
     activeColourInput.setAttribute("name", "active_colour");
+    colourSpaceSelect.setAttribute("name", "colour_space");
+    scaleSelect.setAttribute("name", "scale");
   } else {
     inactiveColour.classList.add('hidden');
     activeColour.classList.add('hidden');
+    colourSpace.classList.add('hidden');
+    scale.classList.add('hidden');
 
     inactiveColourInput.removeAttribute("name");
     activeColourInput.removeAttribute("name");
+    colourSpaceSelect.removeAttribute("name");
+    scaleSelect.removeAttribute("name");
+  }
+
+  if (colourStrategy.value === "QuantileStrategy") {
+    colours.classList.remove('hidden');
+    colours.querySelectorAll("input").forEach((input) => input.setAttribute("name", "colours"));
+  } else {
+    colours.classList.add('hidden');
+    colours.querySelectorAll("input").forEach((input) => input.removeAttribute("name"));
+  }
+
+  if (colourStrategy.value === "Theme") {
+    theme.classList.remove('hidden');
+    themeSelect.setAttribute("name", "theme");
+    colourStrategy.removeAttribute("name");
+  } else {
+    theme.classList.add('hidden');
+    themeSelect.removeAttribute("name");
+    colourStrategy.setAttribute("name", "colour_strategy");
+  }
+}
+
+function darkModeChange(e) {
+  const darkColours = document.querySelector("#dark_colours");
+  darkColours.classList.toggle("hidden", !e.target.checked);
+
+  for (const input of darkColours.querySelectorAll("input")) {
+    if (e.target.checked) {
+      input.setAttribute("name", input.dataset.formName);
+    } else {
+      input.removeAttribute("name");
+    }
   }
 }
 